@@ -0,0 +1,93 @@
+// config.rs
+//! Declarative cluster config files (the `Config` subcommand and the
+//! `--config` flag on `dev`/`prod`), mirroring k3d's own `config init`/
+//! `config view` workflow so a cluster definition is checkable into a
+//! repo instead of living only as CLI flags.
+//!
+//! A file only has to set the fields a particular cluster cares about:
+//! anything it leaves out falls back to the built-in default, and a CLI
+//! flag passed alongside `--config` always overrides the value read from
+//! the file.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// The subset of cluster settings that's meaningful to check into a repo.
+/// Every field is optional so a file can describe just what it needs to
+/// override from the built-in defaults.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterFileConfig {
+    pub name: Option<String>,
+    /// Control-plane node count (`prod` only).
+    pub servers: Option<u8>,
+    /// Worker node count (`prod`'s `agents`, or `dev`'s `workers`).
+    pub agents: Option<u8>,
+    pub install_monitoring: Option<bool>,
+    pub install_logging: Option<bool>,
+    pub install_argocd: Option<bool>,
+    pub install_sso: Option<bool>,
+    /// Auto-create (or reuse) a shared image registry of this name.
+    pub registry: Option<String>,
+    /// Install as a vcluster inside this namespace instead of a dedicated
+    /// k3d cluster (`prod` only).
+    pub vcluster: Option<String>,
+}
+
+const DEFAULT_CONFIG_YAML: &str = r#"# k3d-manager cluster config
+# Any field left out falls back to the built-in default, and any CLI flag
+# passed alongside --config overrides the value read from here.
+
+name: prod-cluster
+
+# Control plane nodes (prod only) / worker nodes (prod's agents, dev's workers)
+servers: 3
+agents: 3
+
+# Which stacks to install
+install_monitoring: true
+install_logging: true
+install_argocd: true
+install_sso: false
+
+# Auto-create (or reuse) a shared image registry and wire it into the
+# cluster. Leave commented out to skip.
+# registry: my-registry
+
+# Install as a vcluster inside this namespace of the current kube context
+# instead of a dedicated k3d cluster (prod only). Leave commented out to skip.
+# vcluster: my-namespace
+"#;
+
+/// Writes a commented default config to `path` (`config init`).
+pub fn init(path: &Path) -> Result<()> {
+    if path.exists() {
+        anyhow::bail!(
+            "{} already exists - remove it first if you want to overwrite it",
+            path.display()
+        );
+    }
+    fs::write(path, DEFAULT_CONFIG_YAML)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    println!("✅ Wrote default config to {}", path.display());
+    Ok(())
+}
+
+/// Reads and deserializes a config file.
+pub fn load(path: &Path) -> Result<ClusterFileConfig> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    serde_yaml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Pretty-prints the config as read from `path` (`config view`). Fields the
+/// file leaves out are printed as `null`; they are not resolved to the
+/// built-in defaults, which differ between `dev` and `prod`.
+pub fn view(path: &Path) -> Result<()> {
+    let config = load(path)?;
+    let pretty = serde_yaml::to_string(&config).context("failed to render config")?;
+    print!("{}", pretty);
+    Ok(())
+}
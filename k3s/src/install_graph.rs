@@ -0,0 +1,282 @@
+// install_graph.rs
+//! Dependency-graph scheduler for Helm component installs.
+//!
+//! Each component to install is modeled as an `Installation` carrying its
+//! name, namespace, and the names of the components it depends on.
+//! `run_graph` dispatches installations as soon as their dependencies report
+//! ready, running independent components concurrently instead of the old
+//! strictly-sequential `sleep`-between-every-step approach.
+
+use crate::notify::{self, Notifier, Phase};
+use crate::utils;
+use anyhow::{anyhow, bail, Result};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+pub type InstallFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// Boxes an install future so it can be handed to `Installation::new`.
+pub fn boxed<F>(f: F) -> InstallFuture
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    Box::pin(f)
+}
+
+/// A single component in the installation dependency graph.
+pub struct Installation {
+    /// Unique node id, also used in dependency references and error messages.
+    pub name: String,
+    pub namespace: String,
+    /// Names of other installations that must be ready before this one starts.
+    pub depends_on: Vec<String>,
+    /// Label selector used to poll readiness via `kubectl wait`.
+    pub ready_selector: String,
+    pub ready_timeout: Duration,
+    install: Box<dyn Fn() -> InstallFuture + Send>,
+}
+
+impl Installation {
+    pub fn new(
+        name: &str,
+        namespace: &str,
+        depends_on: &[&str],
+        ready_selector: &str,
+        ready_timeout: Duration,
+        install: impl Fn() -> InstallFuture + Send + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            ready_selector: ready_selector.to_string(),
+            ready_timeout,
+            install: Box::new(install),
+        }
+    }
+}
+
+/// Polls `kubectl wait` until the component's pods report ready, or errors
+/// once `ready_timeout` elapses.
+fn is_ready(namespace: String, selector: String, timeout: Duration) -> InstallFuture {
+    Box::pin(async move {
+        utils::run(
+            "kubectl",
+            &[
+                "wait",
+                "--namespace",
+                &namespace,
+                "--for=condition=ready",
+                "pod",
+                "--selector",
+                &selector,
+                &format!("--timeout={}s", timeout.as_secs()),
+            ],
+        )
+    })
+}
+
+/// Validates that every dependency name exists and that the graph has no
+/// cycles, by repeatedly peeling off nodes whose dependencies are satisfied.
+///
+/// Takes plain references so callers can validate a graph before it's ever
+/// handed to `run_graph` — in particular, so `create_prod_cluster` can fail
+/// on a bad dependency graph before provisioning a cluster, rather than
+/// discovering it only once `run_graph` hits this same check itself.
+pub(crate) fn detect_cycles(installations: &[&Installation]) -> Result<()> {
+    let by_name: HashMap<&str, &Installation> =
+        installations.iter().map(|i| (i.name.as_str(), *i)).collect();
+
+    for installation in installations {
+        for dep in &installation.depends_on {
+            if !by_name.contains_key(dep.as_str()) {
+                bail!(
+                    "installation '{}' depends on unknown component '{}'",
+                    installation.name,
+                    dep
+                );
+            }
+        }
+    }
+
+    let mut satisfied: HashSet<&str> = HashSet::new();
+    let mut remaining: Vec<&&Installation> = installations.iter().collect();
+
+    while !remaining.is_empty() {
+        let before = remaining.len();
+        remaining.retain(|installation| {
+            let ready = installation
+                .depends_on
+                .iter()
+                .all(|dep| satisfied.contains(dep.as_str()));
+            if ready {
+                satisfied.insert(installation.name.as_str());
+            }
+            !ready
+        });
+
+        if remaining.len() == before {
+            let stuck: Vec<&str> = remaining.iter().map(|i| i.name.as_str()).collect();
+            bail!("dependency cycle detected among: {}", stuck.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves the dependency graph and installs components as soon as their
+/// dependencies are ready. Independent components run concurrently; a
+/// component only starts once every name in `depends_on` has passed its
+/// readiness check. Fires a notification for each component that finishes
+/// installing or fails, via `notifier` if one is configured.
+pub async fn run_graph(
+    installations: Vec<Installation>,
+    cluster: &str,
+    notifier: &Option<Notifier>,
+) -> Result<()> {
+    let by_name: HashMap<String, Installation> = installations
+        .into_iter()
+        .map(|i| (i.name.clone(), i))
+        .collect();
+
+    detect_cycles(&by_name.values().collect::<Vec<&Installation>>())?;
+
+    let total = by_name.len();
+    let mut done: HashSet<String> = HashSet::new();
+    let mut dispatched: HashSet<String> = HashSet::new();
+    let mut in_flight = tokio::task::JoinSet::new();
+
+    while done.len() < total {
+        for installation in by_name.values() {
+            if dispatched.contains(&installation.name) {
+                continue;
+            }
+            if !installation
+                .depends_on
+                .iter()
+                .all(|dep| done.contains(dep))
+            {
+                continue;
+            }
+
+            dispatched.insert(installation.name.clone());
+            println!(
+                "▶️  Installing {} (namespace: {})...",
+                installation.name, installation.namespace
+            );
+
+            let name = installation.name.clone();
+            let namespace = installation.namespace.clone();
+            let selector = installation.ready_selector.clone();
+            let timeout = installation.ready_timeout;
+            let install_fut = (installation.install)();
+
+            // The install/readiness futures are mostly a chain of blocking
+            // `utils::run` shell-outs (only the manifest applies through the
+            // kube client really await), so driving them on a tokio worker thread would monopolize
+            // it for the whole helm install + wait and serialize wave
+            // members on low-core-count runners. `spawn_blocking` runs each
+            // chain on the blocking thread pool instead, so independent
+            // components in a wave actually install concurrently.
+            in_flight.spawn_blocking(move || {
+                let result = tokio::runtime::Handle::current().block_on(async move {
+                    match install_fut.await {
+                        Ok(()) => is_ready(namespace, selector, timeout).await,
+                        Err(e) => Err(e),
+                    }
+                });
+                (name, result)
+            });
+        }
+
+        match in_flight.join_next().await {
+            Some(Ok((name, Ok(())))) => {
+                println!("✅ {} is ready", name);
+                notify::notify(
+                    notifier,
+                    cluster,
+                    Phase::ComponentInstalled,
+                    Some(&name),
+                    "success",
+                    &format!("{} is ready", name),
+                );
+                done.insert(name);
+            }
+            Some(Ok((name, Err(e)))) => {
+                notify::notify(
+                    notifier,
+                    cluster,
+                    Phase::ComponentFailed,
+                    Some(&name),
+                    "failure",
+                    &format!("{:#}", e),
+                );
+                return Err(anyhow!("component '{}' failed: {:#}", name, e));
+            }
+            Some(Err(join_err)) => {
+                return Err(anyhow!("installation task panicked: {}", join_err));
+            }
+            None => {
+                let stuck: Vec<&str> = by_name
+                    .keys()
+                    .filter(|name| !done.contains(*name))
+                    .map(|s| s.as_str())
+                    .collect();
+                bail!(
+                    "installation graph stalled, unable to make progress on: {}",
+                    stuck.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installation(name: &str, depends_on: &[&str]) -> Installation {
+        Installation::new(
+            name,
+            name,
+            depends_on,
+            "app=test",
+            Duration::from_secs(1),
+            || boxed(async { Ok(()) }),
+        )
+    }
+
+    #[test]
+    fn detect_cycles_accepts_a_valid_dag() {
+        let installations = vec![
+            installation("cert-manager", &[]),
+            installation("ingress-nginx", &[]),
+            installation("argocd", &["cert-manager", "ingress-nginx"]),
+        ];
+        let refs: Vec<&Installation> = installations.iter().collect();
+
+        assert!(detect_cycles(&refs).is_ok());
+    }
+
+    #[test]
+    fn detect_cycles_rejects_an_unknown_dependency() {
+        let installations = vec![installation("argocd", &["dex"])];
+        let refs: Vec<&Installation> = installations.iter().collect();
+
+        let err = detect_cycles(&refs).unwrap_err();
+        assert!(err.to_string().contains("unknown component 'dex'"));
+    }
+
+    #[test]
+    fn detect_cycles_rejects_a_direct_cycle() {
+        let installations = vec![installation("a", &["b"]), installation("b", &["a"])];
+        let refs: Vec<&Installation> = installations.iter().collect();
+
+        let err = detect_cycles(&refs).unwrap_err();
+        assert!(err.to_string().contains("dependency cycle detected"));
+    }
+}
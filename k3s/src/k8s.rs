@@ -0,0 +1,243 @@
+// k8s.rs
+//! Native Kubernetes API access via `kube`/`k8s-openapi`, used where the
+//! tool needs to react to actual resource state (readiness conditions,
+//! restart counts) instead of shelling out to `kubectl` and parsing its
+//! table or piping YAML through `kubectl apply -f -`.
+//!
+//! Client construction mirrors `kube::Client::try_default`: try the
+//! in-cluster service account first, then fall back to the named context
+//! in the local kubeconfig, so the same code works whether `k3d-manager`
+//! runs on a workstation or inside the cluster it manages.
+
+use anyhow::{Context, Result};
+use k8s_openapi::api::core::v1::{Node, Pod, Service};
+use kube::api::{Api, DynamicObject, ListParams, Patch, PatchParams, ResourceExt};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::core::GroupVersionKind;
+use kube::discovery::{Discovery, Scope};
+use kube::{Client, Config};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+const FIELD_MANAGER: &str = "k3d-manager";
+
+/// Builds a client for the named kube context (e.g. `k3d-prod-cluster`,
+/// the context `k3d` points `kubectl` at on cluster create), trying an
+/// in-cluster service account first and falling back to the local
+/// kubeconfig.
+async fn client_for_context(context: &str) -> Result<Client> {
+    if let Ok(config) = Config::incluster() {
+        return Client::try_from(config).context("failed to build in-cluster client");
+    }
+
+    let kubeconfig = Kubeconfig::read().context("failed to read local kubeconfig")?;
+    let options = KubeConfigOptions {
+        context: Some(context.to_string()),
+        ..Default::default()
+    };
+    let config = Config::from_custom_kubeconfig(kubeconfig, &options)
+        .await
+        .with_context(|| format!("failed to build config for context {}", context))?;
+    Client::try_from(config).context("failed to build client from kubeconfig")
+}
+
+/// Builds a client against whatever context is currently active (the
+/// `KUBECONFIG`/current-context a prior `kubectl config use-context` or
+/// vcluster kubeconfig export left in place), for call sites that apply
+/// manifests without pinning a specific cluster name.
+async fn client_for_current_context() -> Result<Client> {
+    if let Ok(config) = Config::incluster() {
+        return Client::try_from(config).context("failed to build in-cluster client");
+    }
+    let config = Config::infer()
+        .await
+        .context("failed to infer kube config from environment/kubeconfig")?;
+    Client::try_from(config).context("failed to build client from kubeconfig")
+}
+
+fn multidoc_deserialize(manifest: &str) -> Result<Vec<serde_yaml::Value>> {
+    let mut documents = vec![];
+    for document in serde_yaml::Deserializer::from_str(manifest) {
+        let value = serde_yaml::Value::deserialize(document)
+            .context("failed to parse a document in the manifest")?;
+        if !value.is_null() {
+            documents.push(value);
+        }
+    }
+    Ok(documents)
+}
+
+/// Server-side-applies a (possibly multi-document) YAML manifest against
+/// the dynamic API, discovering the right `Api<DynamicObject>` for each
+/// document's `apiVersion`/`kind` so callers don't need a typed struct per
+/// resource kind.
+pub async fn apply_manifest(manifest: &str) -> Result<()> {
+    let client = client_for_current_context().await?;
+    let discovery = Discovery::new(client.clone())
+        .run()
+        .await
+        .context("failed to discover API resources")?;
+    let patch_params = PatchParams::apply(FIELD_MANAGER).force();
+
+    for document in multidoc_deserialize(manifest)? {
+        let object: DynamicObject =
+            serde_yaml::from_value(document).context("failed to parse manifest document")?;
+        let types = object
+            .types
+            .as_ref()
+            .context("manifest document is missing apiVersion/kind")?;
+        let gvk = GroupVersionKind::try_from(types)
+            .with_context(|| format!("invalid apiVersion/kind: {:?}", types))?;
+        let name = object.name_any();
+
+        let (api_resource, capabilities) = discovery
+            .resolve_gvk(&gvk)
+            .with_context(|| format!("unknown resource kind {}", gvk.kind))?;
+        let api: Api<DynamicObject> = if capabilities.scope == Scope::Cluster {
+            Api::all_with(client.clone(), &api_resource)
+        } else if let Some(namespace) = object.metadata.namespace.as_deref() {
+            Api::namespaced_with(client.clone(), namespace, &api_resource)
+        } else {
+            Api::default_namespaced_with(client.clone(), &api_resource)
+        };
+
+        api.patch(&name, &patch_params, &Patch::Apply(&object))
+            .await
+            .with_context(|| format!("failed to apply {} {}", gvk.kind, name))?;
+    }
+
+    Ok(())
+}
+
+/// Lists `Node`, `Pod`, and `Service` objects through typed `Api<T>` calls
+/// against the cluster's context and reports real readiness conditions and
+/// restart counts, instead of `kubectl get ... -o wide` table text.
+pub async fn cluster_info(cluster_name: &str) -> Result<()> {
+    let client = client_for_context(&format!("k3d-{}", cluster_name)).await?;
+    let list_params = ListParams::default();
+
+    println!("\n📦 Nodes:");
+    let nodes: Api<Node> = Api::all(client.clone());
+    for node in nodes.list(&list_params).await.context("failed to list nodes")?.items {
+        let ready = node
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+            .map(|condition| condition.status.as_str())
+            .unwrap_or("Unknown");
+        println!("   {:<30} Ready={}", node.name_any(), ready);
+    }
+
+    println!("\n📊 All Pods:");
+    let pods: Api<Pod> = Api::all(client.clone());
+    for pod in pods.list(&list_params).await.context("failed to list pods")?.items {
+        let phase = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.phase.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let restarts: i32 = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.container_statuses.as_ref())
+            .map(|statuses| statuses.iter().map(|c| c.restart_count).sum())
+            .unwrap_or(0);
+        println!(
+            "   {:<20} {:<30} {:<10} restarts={}",
+            pod.namespace().unwrap_or_default(),
+            pod.name_any(),
+            phase,
+            restarts
+        );
+    }
+
+    println!("\n🌐 Services:");
+    let services: Api<Service> = Api::all(client);
+    for service in services.list(&list_params).await.context("failed to list services")?.items {
+        let service_type = service
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.type_.clone())
+            .unwrap_or_else(|| "ClusterIP".to_string());
+        println!(
+            "   {:<20} {:<30} {}",
+            service.namespace().unwrap_or_default(),
+            service.name_any(),
+            service_type
+        );
+    }
+
+    Ok(())
+}
+
+const READINESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn node_is_ready(node: &Node) -> bool {
+    node.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .map(|conditions| conditions.iter().any(|c| c.type_ == "Ready" && c.status == "True"))
+        .unwrap_or(false)
+}
+
+fn pod_is_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .map(|status| {
+            status.phase.as_deref() == Some("Succeeded")
+                || status
+                    .conditions
+                    .as_ref()
+                    .map(|conditions| conditions.iter().any(|c| c.type_ == "Ready" && c.status == "True"))
+                    .unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+/// Polls node and `kube-system` core-pod readiness through typed `Api<T>`
+/// calls until everything reports ready or `timeout` elapses, so a hung
+/// bring-up fails fast with a deadline error naming the pods still
+/// pending instead of blocking indefinitely.
+pub async fn wait_cluster_ready(cluster_name: &str, timeout: Duration) -> Result<()> {
+    let client = client_for_context(&format!("k3d-{}", cluster_name)).await?;
+    let nodes: Api<Node> = Api::all(client.clone());
+    let pods: Api<Pod> = Api::namespaced(client, "kube-system");
+    let list_params = ListParams::default();
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let node_list = nodes.list(&list_params).await.context("failed to list nodes")?;
+        let not_ready_nodes: Vec<String> = node_list
+            .items
+            .iter()
+            .filter(|node| !node_is_ready(node))
+            .map(|node| node.name_any())
+            .collect();
+
+        let pod_list = pods.list(&list_params).await.context("failed to list kube-system pods")?;
+        let pending_pods: Vec<String> = pod_list
+            .items
+            .iter()
+            .filter(|pod| !pod_is_ready(pod))
+            .map(|pod| pod.name_any())
+            .collect();
+
+        if not_ready_nodes.is_empty() && pending_pods.is_empty() {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            let mut pending = not_ready_nodes;
+            pending.extend(pending_pods);
+            anyhow::bail!(
+                "timed out after {}s waiting for cluster '{}' to become ready; still pending: {}",
+                timeout.as_secs(),
+                cluster_name,
+                pending.join(", ")
+            );
+        }
+
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+}
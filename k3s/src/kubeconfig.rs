@@ -0,0 +1,48 @@
+// kubeconfig.rs
+//! Kubeconfig export (the `Kubeconfig` subcommand), wrapping `k3d
+//! kubeconfig get`/`merge` so a cluster's credentials can be written to a
+//! standalone file for CI or another tool, instead of `info`'s old habit
+//! of mutating the user's active kubectl context as a side effect of just
+//! reading cluster state.
+
+use crate::utils;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Writes a cluster's kubeconfig to stdout, or to `output` if given.
+pub fn get(name: &str, output: Option<&Path>) -> Result<()> {
+    let output_str = output
+        .map(|path| path.to_str().context("--output path is not valid UTF-8"))
+        .transpose()?;
+
+    let mut args = vec!["kubeconfig", "get", name];
+    if let Some(path) = &output_str {
+        args.push("-o");
+        args.push(path);
+    }
+
+    utils::run("k3d", &args)?;
+
+    if let Some(path) = output_str {
+        println!("✅ Wrote kubeconfig for '{}' to {}", name, path);
+    }
+    Ok(())
+}
+
+/// Merges a cluster's kubeconfig into the default kubeconfig, optionally
+/// switching the active context to it.
+pub fn merge(name: &str, switch_context: bool) -> Result<()> {
+    let mut args = vec!["kubeconfig", "merge", name, "--kubeconfig-merge-default"];
+    if switch_context {
+        args.push("--kubeconfig-switch-context");
+    }
+
+    utils::run("k3d", &args)?;
+
+    println!(
+        "✅ Merged kubeconfig for '{}' into the default kubeconfig{}",
+        name,
+        if switch_context { " and switched context" } else { "" }
+    );
+    Ok(())
+}
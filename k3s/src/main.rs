@@ -2,9 +2,29 @@
 // main.rs
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
 
+mod config;
+mod install_graph;
+mod k8s;
+mod kubeconfig;
+mod notify;
 mod prod_cluster;
+mod registry;
+mod render;
+mod sops;
 mod utils;
+mod validate;
+
+/// Default host port a cluster's auto-created/reused `--registry` listens
+/// on, matching the port the inline `registries: create:` block in
+/// `create_k3d_config` already uses for the per-cluster registry.
+const DEFAULT_REGISTRY_PORT: u16 = 5000;
+
+/// Default `--timeout` bound for `--wait`'s readiness poll when only
+/// `--wait` is passed.
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 300;
 
 #[derive(Parser)]
 #[command(name = "k3d-manager")]
@@ -18,29 +38,45 @@ struct Cli {
 enum Commands {
     /// Create a simple development cluster
     Dev {
-        /// Cluster name
-        #[arg(short, long, default_value = "dev-cluster")]
-        name: String,
-        
-        /// Number of worker nodes
-        #[arg(short, long, default_value = "2")]
-        workers: u8,
+        /// Cluster name (overrides --config)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Number of worker nodes (overrides --config)
+        #[arg(short, long)]
+        workers: Option<u8>,
+
+        /// Auto-create (or reuse) a shared image registry and wire it into the cluster (overrides --config)
+        #[arg(long, value_name = "NAME")]
+        registry: Option<String>,
+
+        /// Load cluster settings from this declarative config file; CLI flags override its values
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Block until every node and kube-system pod reports ready (or --timeout elapses)
+        #[arg(long)]
+        wait: bool,
+
+        /// Bound how long --wait polls before failing (requires --wait)
+        #[arg(long, value_name = "SECONDS")]
+        timeout: Option<u64>,
     },
-    
+
     /// Create a production-like cluster with full stack
     Prod {
-        /// Cluster name
-        #[arg(short, long, default_value = "prod-cluster")]
-        name: String,
-        
-        /// Number of control plane nodes
-        #[arg(short, long, default_value = "3")]
-        servers: u8,
-        
-        /// Number of worker nodes
-        #[arg(short = 'w', long, default_value = "3")]
-        agents: u8,
-        
+        /// Cluster name (overrides --config)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Number of control plane nodes (overrides --config)
+        #[arg(short, long)]
+        servers: Option<u8>,
+
+        /// Number of worker nodes (overrides --config)
+        #[arg(short = 'w', long)]
+        agents: Option<u8>,
+
         /// Skip monitoring stack installation
         #[arg(long)]
         skip_monitoring: bool,
@@ -52,49 +88,364 @@ enum Commands {
         /// Skip ArgoCD installation
         #[arg(long)]
         skip_argocd: bool,
+
+        /// Template each enabled component's chart and validate the inline manifests without creating a cluster (the sample app and cert-issuer are not checked)
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Install as a vcluster inside this namespace of the current kube context instead of a dedicated k3d cluster (overrides --config)
+        #[arg(long, value_name = "HOST_NAMESPACE")]
+        vcluster: Option<String>,
+
+        /// POST a JSON event to this webhook URL at each install milestone
+        #[arg(long, value_name = "URL")]
+        notify_webhook: Option<String>,
+
+        /// Exec this shell command with each install milestone's fields as env vars (CLUSTER, PHASE, COMPONENT, STATUS, MESSAGE, TIMESTAMP)
+        #[arg(long, value_name = "CMD")]
+        notify_command: Option<String>,
+
+        /// Install Dex and wire Grafana, ArgoCD, and Kibana to single sign-on through it
+        #[arg(long)]
+        sso: bool,
+
+        /// Auto-create (or reuse) a shared image registry and wire it into the cluster (overrides --config)
+        #[arg(long, value_name = "NAME")]
+        registry: Option<String>,
+
+        /// Load cluster settings from this declarative config file; CLI flags override its values
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Block until every node and kube-system pod reports ready (or --timeout elapses); ignored with --vcluster
+        #[arg(long)]
+        wait: bool,
+
+        /// Bound how long --wait polls before failing (requires --wait)
+        #[arg(long, value_name = "SECONDS")]
+        timeout: Option<u64>,
+    },
+
+    /// Validate a production cluster config without creating anything (shorthand for `prod --dry-run`)
+    Validate {
+        /// Cluster name (overrides --config)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Skip monitoring stack validation
+        #[arg(long)]
+        skip_monitoring: bool,
+
+        /// Skip logging stack validation
+        #[arg(long)]
+        skip_logging: bool,
+
+        /// Skip ArgoCD validation
+        #[arg(long)]
+        skip_argocd: bool,
+
+        /// Validate the Dex install and the OIDC-wired Grafana/ArgoCD/Kibana manifests too (overrides --config)
+        #[arg(long)]
+        sso: bool,
+
+        /// Load cluster settings from this declarative config file; CLI flags override its values
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
     },
-    
+
+    /// Render the manifests a prod cluster would install (except the sample app) to a directory, plus an apply.sh
+    Render {
+        /// Cluster name (overrides --config)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Skip monitoring stack manifests
+        #[arg(long)]
+        skip_monitoring: bool,
+
+        /// Skip logging stack manifests
+        #[arg(long)]
+        skip_logging: bool,
+
+        /// Skip ArgoCD manifests
+        #[arg(long)]
+        skip_argocd: bool,
+
+        /// Render the Dex install and the OIDC-wired Grafana/ArgoCD/Kibana manifests too (overrides --config)
+        #[arg(long)]
+        sso: bool,
+
+        /// Load cluster settings from this declarative config file; CLI flags override its values
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Directory to write the rendered manifests and apply.sh into
+        #[arg(short, long, default_value = "./rendered")]
+        output: PathBuf,
+    },
+
     /// List all k3d clusters
     List,
-    
+
     /// Delete a cluster
     Delete {
         /// Cluster name
         name: String,
     },
-    
+
     /// Get cluster info
     Info {
         /// Cluster name
         name: String,
     },
+
+    /// Stop a cluster's containers without deleting it
+    Stop {
+        /// Cluster name
+        name: String,
+    },
+
+    /// Start a previously stopped cluster back up
+    Start {
+        /// Cluster name
+        name: String,
+    },
+
+    /// Manage shared local image registries used by `dev --registry`/`prod --registry`
+    Registry {
+        #[command(subcommand)]
+        action: RegistryAction,
+    },
+
+    /// Manage declarative cluster config files used by `dev --config`/`prod --config`
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Export a cluster's kubeconfig for CI or another tool
+    Kubeconfig {
+        #[command(subcommand)]
+        action: KubeconfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum KubeconfigAction {
+    /// Write a cluster's kubeconfig to stdout, or to --output if given
+    Get {
+        /// Cluster name
+        name: String,
+
+        /// Path to write the kubeconfig to, instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Merge a cluster's kubeconfig into the default kubeconfig
+    Merge {
+        /// Cluster name
+        name: String,
+
+        /// Also switch the active context to this cluster
+        #[arg(long)]
+        switch_context: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Write a commented default config file
+    Init {
+        /// Path to write the config file to
+        #[arg(default_value = "k3d-manager.yaml")]
+        file: PathBuf,
+    },
+
+    /// Pretty-print the config as read from the file (omitted fields show as null, not their defaults)
+    View {
+        /// Path to the config file
+        #[arg(default_value = "k3d-manager.yaml")]
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum RegistryAction {
+    /// Create a new registry
+    Create {
+        /// Registry name
+        name: String,
+
+        /// Host port the registry listens on
+        #[arg(short, long, default_value_t = DEFAULT_REGISTRY_PORT)]
+        port: u16,
+    },
+
+    /// Delete a registry
+    Delete {
+        /// Registry name
+        name: String,
+    },
+
+    /// List all registries
+    List,
+
+    /// Wire an existing registry into an already-running cluster
+    Connect {
+        /// Registry name
+        registry: String,
+
+        /// Cluster name
+        cluster: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    
+
+    utils::check_tools()?;
+
     match cli.command {
-        Commands::Dev { name, workers } => {
-            create_dev_cluster(&name, workers).await?;
+        Commands::Dev { name, workers, registry, config, wait, timeout } => {
+            if timeout.is_some() && !wait {
+                anyhow::bail!("--timeout requires --wait");
+            }
+
+            let file_config = match &config {
+                Some(path) => config::load(path)?,
+                None => config::ClusterFileConfig::default(),
+            };
+            let name = name.or(file_config.name).unwrap_or_else(|| "dev-cluster".to_string());
+            let workers = workers.or(file_config.agents).unwrap_or(2);
+            let registry = registry.or(file_config.registry);
+            let wait_timeout = wait.then(|| Duration::from_secs(timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS)));
+
+            create_dev_cluster(&name, workers, registry, wait_timeout).await?;
         }
-        Commands::Prod { 
-            name, 
-            servers, 
+        Commands::Prod {
+            name,
+            servers,
             agents,
             skip_monitoring,
             skip_logging,
             skip_argocd,
+            dry_run,
+            vcluster,
+            notify_webhook,
+            notify_command,
+            sso,
+            registry,
+            config,
+            wait,
+            timeout,
         } => {
-            let config = prod_cluster::ProdClusterConfig {
+            if timeout.is_some() && !wait {
+                anyhow::bail!("--timeout requires --wait");
+            }
+
+            let file_config = match &config {
+                Some(path) => config::load(path)?,
+                None => config::ClusterFileConfig::default(),
+            };
+            let name = name.or(file_config.name).unwrap_or_else(|| "prod-cluster".to_string());
+            let servers = servers.or(file_config.servers).unwrap_or(3);
+            let agents = agents.or(file_config.agents).unwrap_or(3);
+            let install_monitoring = file_config.install_monitoring.unwrap_or(true) && !skip_monitoring;
+            let install_logging = file_config.install_logging.unwrap_or(true) && !skip_logging;
+            let install_argocd = file_config.install_argocd.unwrap_or(true) && !skip_argocd;
+            let install_sso = sso || file_config.install_sso.unwrap_or(false);
+            let vcluster = vcluster.or(file_config.vcluster);
+            let registry = registry.or(file_config.registry);
+
+            let backend = match vcluster {
+                Some(host_namespace) => prod_cluster::ClusterBackend::Vcluster { host_namespace },
+                None => prod_cluster::ClusterBackend::K3d,
+            };
+            let notify = match (notify_webhook, notify_command) {
+                (Some(url), _) => Some(notify::Notifier::Webhook(url)),
+                (None, Some(command)) => Some(notify::Notifier::Command(command)),
+                (None, None) => None,
+            };
+            let prod_config = prod_cluster::ProdClusterConfig {
                 name,
                 servers,
                 agents,
-                install_monitoring: !skip_monitoring,
-                install_logging: !skip_logging,
-                install_argocd: !skip_argocd,
+                install_monitoring,
+                install_logging,
+                install_argocd,
+                dry_run,
+                backend,
+                notify,
+                install_sso,
+                registry: registry.map(|name| (name, DEFAULT_REGISTRY_PORT)),
+                wait_timeout: wait.then(|| Duration::from_secs(timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS))),
             };
-            prod_cluster::create_prod_cluster(config).await?;
+            prod_cluster::create_prod_cluster(prod_config).await?;
+        }
+        Commands::Validate {
+            name,
+            skip_monitoring,
+            skip_logging,
+            skip_argocd,
+            sso,
+            config,
+        } => {
+            let file_config = match &config {
+                Some(path) => config::load(path)?,
+                None => config::ClusterFileConfig::default(),
+            };
+            let name = name.or(file_config.name).unwrap_or_else(|| "prod-cluster".to_string());
+            let install_sso = sso || file_config.install_sso.unwrap_or(false);
+
+            let prod_config = prod_cluster::ProdClusterConfig {
+                name,
+                servers: 3,
+                agents: 3,
+                install_monitoring: file_config.install_monitoring.unwrap_or(true) && !skip_monitoring,
+                install_logging: file_config.install_logging.unwrap_or(true) && !skip_logging,
+                install_argocd: file_config.install_argocd.unwrap_or(true) && !skip_argocd,
+                dry_run: true,
+                backend: prod_cluster::ClusterBackend::K3d,
+                notify: None,
+                install_sso,
+                registry: None,
+                wait_timeout: None,
+            };
+            prod_cluster::create_prod_cluster(prod_config).await?;
+        }
+        Commands::Render {
+            name,
+            skip_monitoring,
+            skip_logging,
+            skip_argocd,
+            sso,
+            config,
+            output,
+        } => {
+            let file_config = match &config {
+                Some(path) => config::load(path)?,
+                None => config::ClusterFileConfig::default(),
+            };
+            let name = name.or(file_config.name).unwrap_or_else(|| "prod-cluster".to_string());
+            let install_sso = sso || file_config.install_sso.unwrap_or(false);
+
+            let prod_config = prod_cluster::ProdClusterConfig {
+                name,
+                servers: 3,
+                agents: 3,
+                install_monitoring: file_config.install_monitoring.unwrap_or(true) && !skip_monitoring,
+                install_logging: file_config.install_logging.unwrap_or(true) && !skip_logging,
+                install_argocd: file_config.install_argocd.unwrap_or(true) && !skip_argocd,
+                dry_run: false,
+                backend: prod_cluster::ClusterBackend::K3d,
+                notify: None,
+                install_sso,
+                registry: None,
+                wait_timeout: None,
+            };
+            render::render(&prod_config, &output)?;
         }
         Commands::List => {
             list_clusters()?;
@@ -103,32 +454,80 @@ async fn main() -> Result<()> {
             delete_cluster(&name)?;
         }
         Commands::Info { name } => {
-            cluster_info(&name)?;
+            cluster_info(&name).await?;
+        }
+        Commands::Stop { name } => {
+            stop_cluster(&name)?;
         }
+        Commands::Start { name } => {
+            start_cluster(&name)?;
+        }
+        Commands::Registry { action } => match action {
+            RegistryAction::Create { name, port } => registry::create(&name, port)?,
+            RegistryAction::Delete { name } => registry::delete(&name)?,
+            RegistryAction::List => registry::list()?,
+            RegistryAction::Connect { registry, cluster } => registry::connect(&registry, &cluster)?,
+        },
+        Commands::Config { action } => match action {
+            ConfigAction::Init { file } => config::init(&file)?,
+            ConfigAction::View { file } => config::view(&file)?,
+        },
+        Commands::Kubeconfig { action } => match action {
+            KubeconfigAction::Get { name, output } => kubeconfig::get(&name, output.as_deref())?,
+            KubeconfigAction::Merge { name, switch_context } => kubeconfig::merge(&name, switch_context)?,
+        },
     }
-    
+
     Ok(())
 }
 
-async fn create_dev_cluster(name: &str, workers: u8) -> Result<()> {
+async fn create_dev_cluster(
+    name: &str,
+    workers: u8,
+    registry: Option<String>,
+    wait_timeout: Option<Duration>,
+) -> Result<()> {
     println!("🚀 Creating dev cluster: {}", name);
     println!("   Workers: {}", workers);
-    
-    utils::run("k3d", &[
-        "cluster", "create", name,
-        "--servers", "1",
-        "--agents", &workers.to_string(),
-        "--port", "8080:80@loadbalancer",
-        "--port", "8443:443@loadbalancer",
-        "--wait",
-    ])?;
-    
+
+    let mut args = vec![
+        "cluster".to_string(), "create".to_string(), name.to_string(),
+        "--servers".to_string(), "1".to_string(),
+        "--agents".to_string(), workers.to_string(),
+        "--port".to_string(), "8080:80@loadbalancer".to_string(),
+        "--port".to_string(), "8443:443@loadbalancer".to_string(),
+        "--wait".to_string(),
+    ];
+
+    if let Some(registry_name) = &registry {
+        registry::ensure(registry_name, DEFAULT_REGISTRY_PORT)?;
+
+        let registry_config_path = format!("/tmp/k3d-{}-registries.yaml", name);
+        registry::write_mirror_config(&registry_config_path, registry_name, DEFAULT_REGISTRY_PORT)?;
+
+        args.push("--registry-config".to_string());
+        args.push(registry_config_path);
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    utils::run("k3d", &arg_refs)?;
+
     println!("✅ Dev cluster '{}' created successfully!", name);
+
+    if let Some(timeout) = wait_timeout {
+        println!("⏳ Waiting up to {}s for nodes and core pods to report ready...", timeout.as_secs());
+        k8s::wait_cluster_ready(name, timeout).await?;
+        println!("✅ Cluster '{}' is ready", name);
+    }
+
+    if let Some(registry_name) = &registry {
+        println!("   Registry:   localhost:{} (shared registry '{}')", DEFAULT_REGISTRY_PORT, registry_name);
+    }
     println!("\n📋 Quick commands:");
     println!("   kubectl get nodes");
     println!("   kubectl config use-context k3d-{}", name);
     println!("   k3d cluster delete {}", name);
-    
+
     Ok(())
 }
 
@@ -145,22 +544,24 @@ fn delete_cluster(name: &str) -> Result<()> {
     Ok(())
 }
 
-fn cluster_info(name: &str) -> Result<()> {
+fn stop_cluster(name: &str) -> Result<()> {
+    println!("⏸️  Stopping cluster: {}", name);
+    utils::run("k3d", &["cluster", "stop", name])?;
+    println!("✅ Cluster '{}' stopped (workloads preserved, bring it back with `start {}`)", name, name);
+    Ok(())
+}
+
+fn start_cluster(name: &str) -> Result<()> {
+    println!("▶️  Starting cluster: {}", name);
+    utils::run("k3d", &["cluster", "start", name])?;
+    println!("✅ Cluster '{}' started", name);
+    Ok(())
+}
+
+async fn cluster_info(name: &str) -> Result<()> {
     println!("ℹ️  Cluster Info: {}\n", name);
-    
-    // Set context
-    utils::run("kubectl", &[
-        "config", "use-context", &format!("k3d-{}", name)
-    ])?;
-    
-    println!("\n📦 Nodes:");
-    utils::run("kubectl", &["get", "nodes", "-o", "wide"])?;
-    
-    println!("\n📊 All Pods:");
-    utils::run("kubectl", &["get", "pods", "-A"])?;
-    
-    println!("\n🌐 Services:");
-    utils::run("kubectl", &["get", "svc", "-A"])?;
-    
+
+    k8s::cluster_info(name).await?;
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,173 @@
+// notify.rs
+//! Pluggable notification hooks fired at install milestones: cluster
+//! created, each component installed or failed, and final ready.
+//!
+//! Configured via `ProdClusterConfig.notify` as either a webhook URL (POSTed
+//! a JSON payload) or a shell command (exec'd with the payload fields as env
+//! vars), inspired by Octopod's `NOTIFICATION_COMMAND`. Lets CI pipelines
+//! react to partial failures instead of scraping stdout emoji lines.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where install-milestone notifications get sent.
+pub enum Notifier {
+    /// POST the JSON payload to this URL.
+    Webhook(String),
+    /// Exec this shell command with the payload fields as env vars.
+    Command(String),
+}
+
+/// A stage in the install lifecycle a notification can report on.
+#[derive(Clone, Copy)]
+pub enum Phase {
+    ClusterCreated,
+    ComponentInstalled,
+    ComponentFailed,
+    Ready,
+}
+
+impl Phase {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Phase::ClusterCreated => "cluster_created",
+            Phase::ComponentInstalled => "component_installed",
+            Phase::ComponentFailed => "component_failed",
+            Phase::Ready => "ready",
+        }
+    }
+}
+
+struct Event<'a> {
+    cluster: &'a str,
+    phase: &'a str,
+    component: Option<&'a str>,
+    status: &'a str,
+    message: &'a str,
+    timestamp: String,
+}
+
+/// Fires `event` through `notifier`, if one is configured. Failures are
+/// logged to stderr but never interrupt the install — a broken webhook or
+/// notification command shouldn't fail the cluster build.
+pub fn notify(
+    notifier: &Option<Notifier>,
+    cluster: &str,
+    phase: Phase,
+    component: Option<&str>,
+    status: &str,
+    message: &str,
+) {
+    let Some(notifier) = notifier else {
+        return;
+    };
+
+    let event = Event {
+        cluster,
+        phase: phase.as_str(),
+        component,
+        status,
+        message,
+        timestamp: unix_timestamp(),
+    };
+
+    if let Err(e) = send(notifier, &event) {
+        eprintln!("⚠️  notification failed: {:#}", e);
+    }
+}
+
+fn send(notifier: &Notifier, event: &Event) -> Result<()> {
+    match notifier {
+        Notifier::Webhook(url) => {
+            let body = to_json(event);
+            let response = reqwest::blocking::Client::new()
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .with_context(|| format!("failed to POST notification to {}", url))?;
+            if !response.status().is_success() {
+                anyhow::bail!("webhook {} returned {}", url, response.status());
+            }
+            Ok(())
+        }
+        Notifier::Command(command) => {
+            let status = Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .env("CLUSTER", event.cluster)
+                .env("PHASE", event.phase)
+                .env("COMPONENT", event.component.unwrap_or(""))
+                .env("STATUS", event.status)
+                .env("MESSAGE", event.message)
+                .env("TIMESTAMP", &event.timestamp)
+                .status()
+                .with_context(|| format!("failed to run notification command: {}", command))?;
+            if !status.success() {
+                anyhow::bail!("notification command exited with {}", status);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn to_json(event: &Event) -> String {
+    serde_json::json!({
+        "cluster": event.cluster,
+        "phase": event.phase,
+        "component": event.component,
+        "status": event.status,
+        "message": event.message,
+        "timestamp": event.timestamp,
+    })
+    .to_string()
+}
+
+fn unix_timestamp() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs().to_string())
+        .unwrap_or_else(|_| "0".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_escapes_control_bytes_in_message() {
+        let event = Event {
+            cluster: "test",
+            phase: "component_failed",
+            component: Some("fluentd"),
+            status: "failure",
+            message: "helm error:\r\nexit\tstatus 1 \"quoted\" \\path",
+            timestamp: "123".to_string(),
+        };
+
+        let body = to_json(&event);
+        let parsed: serde_json::Value = serde_json::from_str(&body).expect("valid JSON");
+        assert_eq!(
+            parsed["message"],
+            "helm error:\r\nexit\tstatus 1 \"quoted\" \\path"
+        );
+        assert_eq!(parsed["component"], "fluentd");
+    }
+
+    #[test]
+    fn to_json_encodes_missing_component_as_null() {
+        let event = Event {
+            cluster: "test",
+            phase: "ready",
+            component: None,
+            status: "success",
+            message: "all good",
+            timestamp: "123".to_string(),
+        };
+
+        let body = to_json(&event);
+        let parsed: serde_json::Value = serde_json::from_str(&body).expect("valid JSON");
+        assert!(parsed["component"].is_null());
+    }
+}
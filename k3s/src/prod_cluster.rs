@@ -5,6 +5,7 @@ use tokio::time::sleep;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use crate::install_graph::{boxed, run_graph, Installation};
 use crate::utils;
 
 pub struct ProdClusterConfig {
@@ -14,6 +15,35 @@ pub struct ProdClusterConfig {
     pub install_monitoring: bool,
     pub install_logging: bool,
     pub install_argocd: bool,
+    /// Template/validate everything this config would install and exit
+    /// instead of provisioning a cluster.
+    pub dry_run: bool,
+    pub backend: ClusterBackend,
+    /// Fires a structured event at each install milestone (cluster created,
+    /// each component installed or failed, final ready) if configured.
+    pub notify: Option<crate::notify::Notifier>,
+    /// Install Dex as an OIDC provider and wire Grafana, ArgoCD, and Kibana
+    /// to single sign-on through it instead of their default per-dashboard
+    /// credentials.
+    pub install_sso: bool,
+    /// Auto-create (or reuse) a shared image registry of this `(name, port)`
+    /// and mirror it into the cluster, instead of the ephemeral
+    /// per-cluster registry the k3d config already creates.
+    pub registry: Option<(String, u16)>,
+    /// Block after cluster creation until every node and kube-system pod
+    /// reports ready, up to this bound, instead of the fixed sleep. `None`
+    /// keeps the old fire-and-forget fixed sleep. Only the k3d backend
+    /// waits; the vcluster backend neither polls nor sleeps.
+    pub wait_timeout: Option<Duration>,
+}
+
+/// How the cluster the components get installed into is provisioned.
+pub enum ClusterBackend {
+    /// A dedicated k3d cluster (the default).
+    K3d,
+    /// A vcluster installed into an existing namespace of the *current*
+    /// kube context, instead of a dedicated cluster.
+    Vcluster { host_namespace: String },
 }
 
 // Path to helm values directory
@@ -27,6 +57,7 @@ pub async fn create_prod_cluster(config: ProdClusterConfig) -> Result<()> {
     println!("   Monitoring: {}", if config.install_monitoring { "✓" } else { "✗" });
     println!("   Logging: {}", if config.install_logging { "✓" } else { "✗" });
     println!("   ArgoCD: {}", if config.install_argocd { "✓" } else { "✗" });
+    println!("   SSO (Dex OIDC): {}", if config.install_sso { "✓" } else { "✗" });
 
     // Check if helm is installed
     let helm_available = check_helm_installed();
@@ -41,63 +72,225 @@ pub async fn create_prod_cluster(config: ProdClusterConfig) -> Result<()> {
         ensure_helm_values_dir()?;
     }
 
-    // Create k3d config file
-    create_k3d_config(&config)?;
-    
-    // Create the cluster with custom config
-    println!("\n🏗️  Creating HA cluster...");
-    utils::run("k3d", &[
-        "cluster", "create", &config.name,
-        "--config", "/tmp/k3d-prod-config.yaml",
-    ])?;
+    if config.dry_run {
+        if !helm_available {
+            anyhow::bail!("--dry-run requires Helm to be installed so charts can be templated");
+        }
+        return crate::validate::validate(&config);
+    }
+
+    // Build the installation DAG up front, before the cluster exists, so a
+    // bad dependency graph (unknown component, cycle) fails in milliseconds
+    // instead of after minutes of cluster bring-up. cert-manager and
+    // ingress-nginx have no dependencies and install concurrently;
+    // everything that routes through ingress or issues certs waits on both;
+    // the EFK stack installs as an explicit elasticsearch -> fluentd ->
+    // kibana chain.
+    let installations = if helm_available {
+        let installations = build_installations(&config);
+        crate::install_graph::detect_cycles(&installations.iter().collect::<Vec<_>>())?;
+        Some(installations)
+    } else {
+        None
+    };
+
+    // Provision the cluster components install into. For k3d this is a
+    // dedicated cluster; for vcluster it's a virtual cluster nested inside
+    // the current kube context, and every kubectl/helm call from here on
+    // needs to be redirected at its kubeconfig.
+    match &config.backend {
+        ClusterBackend::K3d => {
+            if let Some((registry_name, registry_port)) = &config.registry {
+                crate::registry::ensure(registry_name, *registry_port)?;
+            }
 
-    println!("✅ Cluster created! Waiting for nodes...");
-    sleep(Duration::from_secs(10)).await;
+            create_k3d_config(&config)?;
+
+            println!("\n🏗️  Creating HA cluster...");
+            utils::run("k3d", &[
+                "cluster", "create", &config.name,
+                "--config", "/tmp/k3d-prod-config.yaml",
+            ])?;
+
+            println!("✅ Cluster created! Waiting for nodes...");
+            match config.wait_timeout {
+                Some(timeout) => {
+                    println!("⏳ Waiting up to {}s for nodes and core pods to report ready...", timeout.as_secs());
+                    crate::k8s::wait_cluster_ready(&config.name, timeout).await?;
+                    println!("✅ Cluster is ready");
+                }
+                None => sleep(Duration::from_secs(10)).await,
+            }
+        }
+        ClusterBackend::Vcluster { host_namespace } => {
+            install_vcluster(&config.name, host_namespace)?;
+
+            println!("✅ vcluster created! Fetching its kubeconfig...");
+            let kubeconfig_path = fetch_vcluster_kubeconfig(&config.name, host_namespace)?;
+            std::env::set_var("KUBECONFIG", &kubeconfig_path);
+            println!("   Using kubeconfig: {}", kubeconfig_path);
+        }
+    }
+
+    crate::notify::notify(
+        &config.notify,
+        &config.name,
+        crate::notify::Phase::ClusterCreated,
+        None,
+        "success",
+        "cluster created",
+    );
 
     // Verify cluster health
     utils::run("kubectl", &["get", "nodes", "-o", "wide"])?;
-    
+
     if !helm_available {
         println!("\n⚠️  Skipping Helm installations (Helm not available)");
-        setup_namespaces()?;
+        setup_namespaces().await?;
         println!("\n✅ Basic cluster '{}' is ready!", config.name);
         print_basic_access_info(&config.name);
         return Ok(());
     }
     
     println!("\n📦 Installing core components via Helm...");
-    
+
     // Add Helm repositories
     setup_helm_repos().await?;
-    
-    // Install core infrastructure
-    install_cert_manager_helm().await?;
-    install_ingress_controller_helm().await?;
-    
+
+    // Namespaces/policies/quotas are plain manifests with no Helm release to
+    // wait on, so they're applied up front rather than threaded through the
+    // installation graph below.
+    setup_namespaces().await?;
+    setup_network_policies().await?;
+    setup_resource_quotas().await?;
+
+    // `installations` was already built and validated for cycles before the
+    // cluster was created above; `helm_available` can't have changed since.
+    let installations = installations.expect("installations built when helm_available");
+
+    run_graph(installations, &config.name, &config.notify).await?;
+
+    println!("\n🎉 Production cluster '{}' is ready!", config.name);
+    crate::notify::notify(
+        &config.notify,
+        &config.name,
+        crate::notify::Phase::Ready,
+        None,
+        "success",
+        "production cluster is ready",
+    );
+    print_access_info(&config.name, config.install_sso);
+
+    Ok(())
+}
+
+/// Builds the installation DAG for `config`: cert-manager and ingress-nginx
+/// have no dependencies and install concurrently; everything that routes
+/// through ingress or issues certs waits on both; the EFK stack installs as
+/// an explicit elasticsearch -> fluentd -> kibana chain. Pure aside from
+/// capturing `config` in the install closures — safe to call before the
+/// cluster it describes exists, so the graph can be validated up front.
+fn build_installations(config: &ProdClusterConfig) -> Vec<Installation> {
+    let mut installations = vec![
+        Installation::new(
+            "cert-manager",
+            "cert-manager",
+            &[],
+            "app.kubernetes.io/instance=cert-manager",
+            Duration::from_secs(300),
+            || boxed(install_cert_manager_helm()),
+        ),
+        Installation::new(
+            "ingress-nginx",
+            "ingress-nginx",
+            &[],
+            "app.kubernetes.io/component=controller",
+            Duration::from_secs(300),
+            || boxed(install_ingress_controller_helm()),
+        ),
+    ];
+
+    // When SSO is enabled, Dex needs cert-manager for its TLS cert and must
+    // be ready before anything tries to federate logins through it.
+    if config.install_sso {
+        installations.push(Installation::new(
+            "dex",
+            "dex",
+            &["cert-manager"],
+            "app.kubernetes.io/instance=dex",
+            Duration::from_secs(180),
+            || boxed(install_dex_helm()),
+        ));
+    }
+
+    let sso_dep: &[&str] = if config.install_sso { &["dex"] } else { &[] };
+    let sso = config.install_sso;
+
     if config.install_monitoring {
-        install_monitoring_stack_helm().await?;
+        let mut depends_on = vec!["cert-manager", "ingress-nginx"];
+        depends_on.extend_from_slice(sso_dep);
+        installations.push(Installation::new(
+            "kube-prometheus-stack",
+            "monitoring",
+            &depends_on,
+            "app.kubernetes.io/instance=kube-prometheus-stack",
+            Duration::from_secs(300),
+            move || boxed(install_monitoring_stack_helm(sso)),
+        ));
     }
-    
+
     if config.install_logging {
-        install_logging_stack_helm().await?;
+        installations.push(Installation::new(
+            "elasticsearch",
+            "logging",
+            &["cert-manager", "ingress-nginx"],
+            "app=elasticsearch-master",
+            Duration::from_secs(300),
+            || boxed(install_elasticsearch_helm()),
+        ));
+        installations.push(Installation::new(
+            "fluentd",
+            "logging",
+            &["elasticsearch"],
+            "app.kubernetes.io/instance=fluentd",
+            Duration::from_secs(300),
+            || boxed(install_fluentd_helm()),
+        ));
+        let mut kibana_depends_on = vec!["fluentd"];
+        kibana_depends_on.extend_from_slice(sso_dep);
+        installations.push(Installation::new(
+            "kibana",
+            "logging",
+            &kibana_depends_on,
+            "app=kibana-kibana",
+            Duration::from_secs(300),
+            move || boxed(install_kibana_helm(sso)),
+        ));
     }
-    
+
     if config.install_argocd {
-        install_argocd_helm().await?;
+        let mut depends_on = vec!["cert-manager", "ingress-nginx"];
+        depends_on.extend_from_slice(sso_dep);
+        installations.push(Installation::new(
+            "argocd",
+            "argocd",
+            &depends_on,
+            "app.kubernetes.io/name=argocd-server",
+            Duration::from_secs(300),
+            move || boxed(install_argocd_helm(sso)),
+        ));
     }
-    
-    // Setup namespaces and policies
-    setup_namespaces()?;
-    setup_network_policies()?;
-    setup_resource_quotas()?;
-    
-    // Deploy sample application
-    deploy_sample_app_helm().await?;
 
-    println!("\n🎉 Production cluster '{}' is ready!", config.name);
-    print_access_info(&config.name);
-    
-    Ok(())
+    installations.push(Installation::new(
+        "sample-app",
+        "production",
+        &["cert-manager", "ingress-nginx"],
+        "app=nginx",
+        Duration::from_secs(120),
+        || boxed(deploy_sample_app_helm()),
+    ));
+
+    installations
 }
 
 fn check_helm_installed() -> bool {
@@ -202,15 +395,200 @@ fn create_sample_nginx_chart() -> Result<()> {
     Ok(())
 }
 
-fn get_values_file(component: &str) -> String {
-    format!("{}/{}.yaml", HELM_VALUES_DIR, component)
+/// Resolves a component's values file, preferring a SOPS-encrypted
+/// `*.sops.yaml` variant over the plaintext one if both exist.
+pub(crate) fn get_values_file(component: &str) -> String {
+    let sops_path = format!("{}/{}.sops.yaml", HELM_VALUES_DIR, component);
+    if Path::new(&sops_path).exists() {
+        sops_path
+    } else {
+        format!("{}/{}.yaml", HELM_VALUES_DIR, component)
+    }
 }
 
-fn get_manifest_file(name: &str) -> String {
-    format!("{}/manifests/{}.yaml", HELM_VALUES_DIR, name)
+/// Resolves a manifest's path under `manifests/`, preferring a SOPS-encrypted
+/// `*.sops.yaml` variant over the plaintext one if both exist.
+pub(crate) fn get_manifest_file(name: &str) -> String {
+    let sops_path = format!("{}/manifests/{}.sops.yaml", HELM_VALUES_DIR, name);
+    if Path::new(&sops_path).exists() {
+        sops_path
+    } else {
+        format!("{}/manifests/{}.yaml", HELM_VALUES_DIR, name)
+    }
+}
+
+/// Resolves a manifest's content: the file under `manifests/` if present
+/// (transparently decrypted if it's SOPS-encrypted), otherwise the crate's
+/// built-in fallback. Shared by the `setup_*` functions below and the
+/// `--dry-run` validation checks, so both apply (or template-check) exactly
+/// the same YAML.
+/// Resolves a named manifest override, falling back to the crate's built-in
+/// manifest only when no override file exists. If an override file is
+/// present but fails to decrypt/read (bad or rotated age key, `sops` not
+/// installed, etc.), this is a hard error rather than a silent substitution
+/// of the default manifest for a user's customized policy.
+pub(crate) fn resolve_manifest(name: &str, fallback: &str) -> Result<String> {
+    let manifest_file = get_manifest_file(name);
+    if Path::new(&manifest_file).exists() {
+        crate::sops::read_to_string(&manifest_file)
+            .with_context(|| format!("failed to resolve manifest override '{}'", manifest_file))
+    } else {
+        Ok(fallback.to_string())
+    }
 }
 
+pub(crate) const CERT_ISSUER_MANIFEST_FALLBACK: &str = r#"
+apiVersion: cert-manager.io/v1
+kind: ClusterIssuer
+metadata:
+  name: selfsigned-issuer
+spec:
+  selfSigned: {}
+---
+apiVersion: cert-manager.io/v1
+kind: Certificate
+metadata:
+  name: local-ca
+  namespace: cert-manager
+spec:
+  isCA: true
+  commonName: local-ca
+  secretName: local-ca-secret
+  privateKey:
+    algorithm: ECDSA
+    size: 256
+  issuerRef:
+    name: selfsigned-issuer
+    kind: ClusterIssuer
+---
+apiVersion: cert-manager.io/v1
+kind: ClusterIssuer
+metadata:
+  name: local-ca-issuer
+spec:
+  ca:
+    secretName: local-ca-secret
+"#;
+
+pub(crate) const DEX_TLS_MANIFEST_FALLBACK: &str = r#"
+apiVersion: cert-manager.io/v1
+kind: Certificate
+metadata:
+  name: dex-tls
+  namespace: dex
+spec:
+  secretName: dex-tls
+  dnsNames:
+    - dex.local
+  issuerRef:
+    name: local-ca-issuer
+    kind: ClusterIssuer
+"#;
+
+pub(crate) const NAMESPACES_MANIFEST_FALLBACK: &str = r#"
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: production
+  labels:
+    environment: production
+---
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: staging
+  labels:
+    environment: staging
+---
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: development
+  labels:
+    environment: development
+"#;
+
+pub(crate) const NETWORK_POLICIES_MANIFEST_FALLBACK: &str = r#"
+apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: default-deny-ingress
+  namespace: production
+spec:
+  podSelector: {}
+  policyTypes:
+  - Ingress
+---
+apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: allow-same-namespace
+  namespace: production
+spec:
+  podSelector: {}
+  policyTypes:
+  - Ingress
+  ingress:
+  - from:
+    - podSelector: {}
+"#;
+
+pub(crate) const RESOURCE_QUOTAS_MANIFEST_FALLBACK: &str = r#"
+apiVersion: v1
+kind: ResourceQuota
+metadata:
+  name: compute-quota
+  namespace: production
+spec:
+  hard:
+    requests.cpu: "10"
+    requests.memory: 20Gi
+    limits.cpu: "20"
+    limits.memory: 40Gi
+---
+apiVersion: v1
+kind: LimitRange
+metadata:
+  name: resource-limits
+  namespace: production
+spec:
+  limits:
+  - max:
+      cpu: "2"
+      memory: 4Gi
+    min:
+      cpu: 100m
+      memory: 128Mi
+    default:
+      cpu: 500m
+      memory: 512Mi
+    defaultRequest:
+      cpu: 250m
+      memory: 256Mi
+    type: Container
+"#;
+
 fn create_k3d_config(config: &ProdClusterConfig) -> Result<()> {
+    // The shared `--registry` (if any) is layered on top of the cluster's
+    // own ephemeral `registry.localhost` via `use:`/`config:` rather than
+    // replacing it, so existing manifests that pull from the per-cluster
+    // registry keep working.
+    let shared_registry = match &config.registry {
+        Some((name, port)) => format!(
+            r#"
+  use:
+    - {container}:{port}
+  config: |
+    mirrors:
+      "localhost:{port}":
+        endpoint:
+          - "http://{container}:{port}""#,
+            container = crate::registry::container_name(name),
+            port = port,
+        ),
+        None => String::new(),
+    };
+
     let yaml_config = format!(r#"
 apiVersion: k3d.io/v1alpha5
 kind: Simple
@@ -249,7 +627,7 @@ registries:
   create:
     name: registry.localhost
     host: "0.0.0.0"
-    hostPort: "5000"
+    hostPort: "5000"{}
 
 options:
   k3s:
@@ -263,15 +641,69 @@ options:
   kubeconfig:
     updateDefaultKubeconfig: true
     switchCurrentContext: true
-"#, config.name, config.servers, config.agents);
+"#, config.name, config.servers, config.agents, shared_registry);
 
     fs::write("/tmp/k3d-prod-config.yaml", yaml_config)
         .context("Failed to write k3d config")?;
-    
+
     println!("✅ Created k3d configuration");
     Ok(())
 }
 
+fn vcluster_kubeconfig_path(name: &str) -> String {
+    format!("/tmp/vcluster-{}-kubeconfig.yaml", name)
+}
+
+/// Installs the vcluster chart into `host_namespace` of whatever cluster
+/// the current kube context points at, then waits for the vcluster pod to
+/// come up.
+fn install_vcluster(name: &str, host_namespace: &str) -> Result<()> {
+    println!(
+        "\n🧩 Installing vcluster '{}' into host namespace '{}'...",
+        name, host_namespace
+    );
+
+    utils::run("kubectl", &["create", "namespace", host_namespace])?;
+
+    utils::run("helm", &[
+        "install", name, "vcluster",
+        "--repo", "https://charts.loft.sh",
+        "--namespace", host_namespace,
+    ])?;
+
+    utils::run("kubectl", &[
+        "wait", "--namespace", host_namespace,
+        "--for=condition=ready", "pod",
+        "--selector", &format!("app=vcluster,release={}", name),
+        "--timeout=300s",
+    ])?;
+
+    Ok(())
+}
+
+/// Decodes the vcluster's generated kubeconfig secret to a temp file and
+/// returns its path, so callers can point `KUBECONFIG` at the virtual
+/// cluster instead of the host.
+fn fetch_vcluster_kubeconfig(name: &str, host_namespace: &str) -> Result<String> {
+    let path = vcluster_kubeconfig_path(name);
+    let secret_name = format!("vc-{}", name);
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!(
+            "kubectl get secret {} -n {} -o jsonpath='{{.data.config}}' | base64 --decode > {}",
+            secret_name, host_namespace, path
+        ))
+        .status()
+        .context("failed to fetch vcluster kubeconfig")?;
+
+    if !status.success() {
+        anyhow::bail!("failed to fetch kubeconfig for vcluster '{}'", name);
+    }
+
+    Ok(path)
+}
+
 async fn setup_helm_repos() -> Result<()> {
     println!("\n📚 Adding Helm repositories...");
     
@@ -282,6 +714,7 @@ async fn setup_helm_repos() -> Result<()> {
         ("elastic", "https://helm.elastic.co"),
         ("fluent", "https://fluent.github.io/helm-charts"),
         ("argo", "https://argoproj.github.io/argo-helm"),
+        ("dex", "https://charts.dexidp.io"),
     ];
     
     for (name, url) in repos {
@@ -300,74 +733,34 @@ async fn install_cert_manager_helm() -> Result<()> {
     utils::run("kubectl", &["create", "namespace", "cert-manager"])?;
     
     let values_file = get_values_file("cert-manager");
-    let values_exists = Path::new(&values_file).exists();
-    
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
     let mut args = vec![
         "install", "cert-manager", "jetstack/cert-manager",
         "--namespace", "cert-manager",
         "--version", "v1.13.2",
         "--set", "installCRDs=true",
     ];
-    
-    if values_exists {
+
+    if let Some(resolved) = &resolved_values {
         args.push("--values");
-        args.push(&values_file);
+        args.push(resolved.as_str());
     } else {
         println!("   ℹ️  Using default values (no custom values file found)");
     }
-    
+
     utils::run("helm", &args)?;
-    
-    sleep(Duration::from_secs(30)).await;
-    
-    utils::run("kubectl", &[
-        "wait", "--for=condition=ready", "pod",
-        "-l", "app.kubernetes.io/instance=cert-manager",
-        "-n", "cert-manager",
-        "--timeout=300s"
-    ])?;
-    
-    // Apply cert issuer from manifest file
-    let issuer_manifest = get_manifest_file("cert-issuer");
-    if Path::new(&issuer_manifest).exists() {
-        utils::run("kubectl", &["apply", "-f", &issuer_manifest])?;
-    } else {
-        // Fallback to inline manifest
-        let issuer = r#"
-apiVersion: cert-manager.io/v1
-kind: ClusterIssuer
-metadata:
-  name: selfsigned-issuer
-spec:
-  selfSigned: {}
----
-apiVersion: cert-manager.io/v1
-kind: Certificate
-metadata:
-  name: local-ca
-  namespace: cert-manager
-spec:
-  isCA: true
-  commonName: local-ca
-  secretName: local-ca-secret
-  privateKey:
-    algorithm: ECDSA
-    size: 256
-  issuerRef:
-    name: selfsigned-issuer
-    kind: ClusterIssuer
----
-apiVersion: cert-manager.io/v1
-kind: ClusterIssuer
-metadata:
-  name: local-ca-issuer
-spec:
-  ca:
-    secretName: local-ca-secret
-"#;
-        utils::apply_manifest(issuer)?;
-    }
-    
+    // The ClusterIssuer/Certificate get applied right away; cert-manager's
+    // controller retries reconciliation until its webhook is up, so this
+    // doesn't need to block on pod readiness itself (the installation graph
+    // gates cert-manager's dependents on that instead).
+    let issuer = resolve_manifest("cert-issuer", CERT_ISSUER_MANIFEST_FALLBACK)?;
+    crate::k8s::apply_manifest(&issuer).await?;
+
     println!("✅ Cert-manager installed via Helm");
     Ok(())
 }
@@ -378,16 +771,20 @@ async fn install_ingress_controller_helm() -> Result<()> {
     utils::run("kubectl", &["create", "namespace", "ingress-nginx"])?;
     
     let values_file = get_values_file("ingress-nginx");
-    let values_exists = Path::new(&values_file).exists();
-    
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
     let mut args = vec![
         "install", "ingress-nginx", "ingress-nginx/ingress-nginx",
         "--namespace", "ingress-nginx",
     ];
-    
-    if values_exists {
+
+    if let Some(resolved) = &resolved_values {
         args.push("--values");
-        args.push(&values_file);
+        args.push(resolved.as_str());
     } else {
         println!("   ℹ️  Using default values");
         args.push("--set");
@@ -395,153 +792,275 @@ async fn install_ingress_controller_helm() -> Result<()> {
         args.push("--set");
         args.push("controller.service.type=NodePort");
     }
-    
+
     utils::run("helm", &args)?;
-    
-    sleep(Duration::from_secs(20)).await;
-    
-    utils::run("kubectl", &[
-        "wait", "--namespace", "ingress-nginx",
-        "--for=condition=ready", "pod",
-        "--selector=app.kubernetes.io/component=controller",
-        "--timeout=300s"
-    ])?;
-    
+
     println!("✅ NGINX Ingress installed via Helm");
     Ok(())
 }
 
-async fn install_monitoring_stack_helm() -> Result<()> {
+/// Dex's static OIDC clients for the three dashboards SSO wires up, as
+/// `(client_id, redirect_uri)` pairs, registered by `install_dex_helm`. The
+/// per-dashboard OIDC overlays below spell out the same client ids and
+/// `<client_id>-secret` secrets directly rather than reading this table.
+const DEX_STATIC_CLIENTS: &[(&str, &str)] = &[
+    ("grafana", "https://grafana.local/login/generic_oauth"),
+    ("argocd", "https://argocd.local/auth/callback"),
+    ("kibana", "https://kibana.local/api/security/oidc/callback"),
+];
+
+/// `--set` flags that wire Grafana's generic OAuth provider to Dex, applied
+/// by `install_monitoring_stack_helm` when SSO is enabled and no custom
+/// values file overrides them. Exposed so `render`/`validate` can preview
+/// the exact overlay a real `prod --sso` run would apply.
+pub(crate) const GRAFANA_OIDC_SETS: &[(&str, &str)] = &[
+    ("grafana.grafana.ini.auth.generic_oauth.enabled", "true"),
+    ("grafana.grafana.ini.auth.generic_oauth.name", "Dex"),
+    ("grafana.grafana.ini.auth.generic_oauth.client_id", "grafana"),
+    ("grafana.grafana.ini.auth.generic_oauth.client_secret", "grafana-secret"),
+    ("grafana.grafana.ini.auth.generic_oauth.auth_url", "https://dex.local/auth"),
+    ("grafana.grafana.ini.auth.generic_oauth.token_url", "https://dex.local/token"),
+    ("grafana.grafana.ini.auth.generic_oauth.api_url", "https://dex.local/userinfo"),
+];
+
+/// `--set` flag wiring Kibana's OIDC realm to Dex, applied by
+/// `install_kibana_helm` under the same conditions as `GRAFANA_OIDC_SETS`.
+pub(crate) const KIBANA_OIDC_SET: (&str, &str) = (
+    "kibanaConfig.kibana\\.yml",
+    "xpack.security.authc.providers.oidc.dex.order=0\nxpack.security.authc.providers.oidc.dex.realm=dex\nxpack.security.authc.providers.oidc.dex.rp.client_id=kibana\nxpack.security.authc.providers.oidc.dex.rp.client_secret=kibana-secret\nxpack.security.authc.providers.oidc.dex.op.issuer=https://dex.local\n",
+);
+
+/// `--set` flag pointing ArgoCD's external URL at the local ingress host,
+/// applied by `install_argocd_helm` under the same conditions above.
+pub(crate) const ARGOCD_OIDC_SET: (&str, &str) = ("configs.cm.url", "https://argocd.local");
+
+/// `--set-string` flag wiring ArgoCD's `oidc.config` ConfigMap field to Dex,
+/// applied alongside `ARGOCD_OIDC_SET`.
+pub(crate) const ARGOCD_OIDC_SET_STRING: (&str, &str) = (
+    "configs.cm.oidc\\.config",
+    "name: Dex\nissuer: https://dex.local\nclientID: argocd\nclientSecret: argocd-secret\nrequestedScopes:\n  - openid\n  - profile\n  - email",
+);
+
+/// Installs Dex as the cluster's OIDC provider, issuing it a TLS cert from
+/// the `local-ca-issuer` ClusterIssuer cert-manager already set up, and
+/// registering a static OIDC client per SSO-enabled dashboard.
+async fn install_dex_helm() -> Result<()> {
+    println!("\n🔑 Installing Dex (OIDC provider) via Helm...");
+
+    utils::run("kubectl", &["create", "namespace", "dex"])?;
+
+    let dex_tls = resolve_manifest("dex-tls", DEX_TLS_MANIFEST_FALLBACK)?;
+    crate::k8s::apply_manifest(&dex_tls).await?;
+
+    let values_file = get_values_file("dex");
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
+    let mut args = vec![
+        "install".to_string(), "dex".to_string(), "dex/dex".to_string(),
+        "--namespace".to_string(), "dex".to_string(),
+    ];
+
+    if let Some(resolved) = &resolved_values {
+        args.push("--values".to_string());
+        args.push(resolved.as_str().to_string());
+    } else {
+        println!("   ℹ️  Using default values with static OIDC clients for grafana/argocd/kibana");
+        args.push("--set".to_string());
+        args.push("https.enabled=true".to_string());
+        args.push("--set".to_string());
+        args.push("https.tlsSecret=dex-tls".to_string());
+
+        for (i, (client_id, redirect_uri)) in DEX_STATIC_CLIENTS.iter().enumerate() {
+            args.push("--set".to_string());
+            args.push(format!("config.staticClients[{}].id={}", i, client_id));
+            args.push("--set".to_string());
+            args.push(format!("config.staticClients[{}].name={}", i, client_id));
+            args.push("--set".to_string());
+            args.push(format!("config.staticClients[{}].secret={}-secret", i, client_id));
+            args.push("--set".to_string());
+            args.push(format!("config.staticClients[{}].redirectURIs[0]={}", i, redirect_uri));
+        }
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    utils::run("helm", &arg_refs)?;
+
+    println!("✅ Dex installed via Helm");
+    Ok(())
+}
+
+async fn install_monitoring_stack_helm(sso: bool) -> Result<()> {
     println!("\n📊 Installing kube-prometheus-stack via Helm...");
-    
+
     utils::run("kubectl", &["create", "namespace", "monitoring"])?;
-    
+
     let values_file = get_values_file("kube-prometheus-stack");
-    let values_exists = Path::new(&values_file).exists();
-    
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
+    let sso_sets: Vec<String> = GRAFANA_OIDC_SETS.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+
     let mut args = vec![
         "install", "kube-prometheus-stack",
         "prometheus-community/kube-prometheus-stack",
         "--namespace", "monitoring",
         "--version", "54.2.2",
     ];
-    
-    if values_exists {
+
+    if let Some(resolved) = &resolved_values {
         args.push("--values");
-        args.push(&values_file);
+        args.push(resolved.as_str());
     } else {
         println!("   ℹ️  Using default values");
+        if sso {
+            for set in &sso_sets {
+                args.push("--set");
+                args.push(set.as_str());
+            }
+        }
     }
-    
+
     utils::run("helm", &args)?;
-    
-    sleep(Duration::from_secs(30)).await;
-    
+
     println!("✅ Monitoring stack installed via Helm");
     Ok(())
 }
 
-async fn install_logging_stack_helm() -> Result<()> {
-    println!("\n📝 Installing EFK stack via Helm...");
-    
+async fn install_elasticsearch_helm() -> Result<()> {
+    println!("\n📝 Installing Elasticsearch via Helm...");
+
     utils::run("kubectl", &["create", "namespace", "logging"])?;
-    
-    // Install Elasticsearch
-    println!("   Installing Elasticsearch...");
+
     let es_values = get_values_file("elasticsearch");
-    let es_values_exists = Path::new(&es_values).exists();
-    
+    let resolved_values = if Path::new(&es_values).exists() {
+        Some(crate::sops::resolve_file(&es_values)?)
+    } else {
+        None
+    };
+
     let mut es_args = vec![
         "install", "elasticsearch", "elastic/elasticsearch",
         "--namespace", "logging",
         "--version", "8.5.1",
     ];
-    
-    if es_values_exists {
+
+    if let Some(resolved) = &resolved_values {
         es_args.push("--values");
-        es_args.push(&es_values);
+        es_args.push(resolved.as_str());
     } else {
         es_args.push("--set");
         es_args.push("replicas=1");
         es_args.push("--set");
         es_args.push("minimumMasterNodes=1");
     }
-    
+
     utils::run("helm", &es_args)?;
-    
-    sleep(Duration::from_secs(30)).await;
-    
-    // Install Fluentd
-    println!("   Installing Fluentd...");
+
+    println!("✅ Elasticsearch installed via Helm");
+    Ok(())
+}
+
+async fn install_fluentd_helm() -> Result<()> {
+    println!("\n📝 Installing Fluentd via Helm...");
+
     let fluentd_values = get_values_file("fluentd");
-    let fluentd_values_exists = Path::new(&fluentd_values).exists();
-    
+    let resolved_values = if Path::new(&fluentd_values).exists() {
+        Some(crate::sops::resolve_file(&fluentd_values)?)
+    } else {
+        None
+    };
+
     let mut fluentd_args = vec![
         "install", "fluentd", "fluent/fluentd",
         "--namespace", "logging",
     ];
-    
-    if fluentd_values_exists {
+
+    if let Some(resolved) = &resolved_values {
         fluentd_args.push("--values");
-        fluentd_args.push(&fluentd_values);
+        fluentd_args.push(resolved.as_str());
     }
-    
+
     utils::run("helm", &fluentd_args)?;
-    
-    // Install Kibana
-    println!("   Installing Kibana...");
+
+    println!("✅ Fluentd installed via Helm");
+    Ok(())
+}
+
+async fn install_kibana_helm(sso: bool) -> Result<()> {
+    println!("\n📝 Installing Kibana via Helm...");
+
     let kibana_values = get_values_file("kibana");
-    let kibana_values_exists = Path::new(&kibana_values).exists();
-    
+    let resolved_values = if Path::new(&kibana_values).exists() {
+        Some(crate::sops::resolve_file(&kibana_values)?)
+    } else {
+        None
+    };
+
+    let kibana_sso_set = format!("{}={}", KIBANA_OIDC_SET.0, KIBANA_OIDC_SET.1);
+
     let mut kibana_args = vec![
         "install", "kibana", "elastic/kibana",
         "--namespace", "logging",
         "--version", "8.5.1",
     ];
-    
-    if kibana_values_exists {
+
+    if let Some(resolved) = &resolved_values {
         kibana_args.push("--values");
-        kibana_args.push(&kibana_values);
+        kibana_args.push(resolved.as_str());
+    } else if sso {
+        kibana_args.push("--set");
+        kibana_args.push(kibana_sso_set.as_str());
     }
-    
+
     utils::run("helm", &kibana_args)?;
-    
-    println!("✅ EFK stack installed via Helm");
+
+    println!("✅ Kibana installed via Helm");
     Ok(())
 }
 
-async fn install_argocd_helm() -> Result<()> {
+async fn install_argocd_helm(sso: bool) -> Result<()> {
     println!("\n🔄 Installing ArgoCD via Helm...");
-    
+
     utils::run("kubectl", &["create", "namespace", "argocd"])?;
-    
+
     let values_file = get_values_file("argocd");
-    let values_exists = Path::new(&values_file).exists();
-    
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
+    let argocd_sso_set = format!("{}={}", ARGOCD_OIDC_SET.0, ARGOCD_OIDC_SET.1);
+    let argocd_sso_set_string = format!("{}={}", ARGOCD_OIDC_SET_STRING.0, ARGOCD_OIDC_SET_STRING.1);
+
     let mut args = vec![
         "install", "argocd", "argo/argo-cd",
         "--namespace", "argocd",
         "--version", "5.51.6",
     ];
-    
-    if values_exists {
+
+    if let Some(resolved) = &resolved_values {
         args.push("--values");
-        args.push(&values_file);
+        args.push(resolved.as_str());
     } else {
         println!("   ℹ️  Using default values");
+        if sso {
+            args.push("--set");
+            args.push(argocd_sso_set.as_str());
+            args.push("--set-string");
+            args.push(argocd_sso_set_string.as_str());
+        }
     }
-    
+
     utils::run("helm", &args)?;
-    
-    sleep(Duration::from_secs(30)).await;
-    
-    utils::run("kubectl", &[
-        "wait", "--namespace", "argocd",
-        "--for=condition=ready", "pod",
-        "--selector=app.kubernetes.io/name=argocd-server",
-        "--timeout=300s"
-    ])?;
-    
+
     println!("✅ ArgoCD installed via Helm");
     Ok(())
 }
@@ -560,15 +1079,14 @@ async fn deploy_sample_app_helm() -> Result<()> {
         ])?;
     } else {
         println!("   ℹ️  Custom chart not found, deploying basic NGINX with kubectl...");
-        deploy_basic_nginx()?;
+        deploy_basic_nginx().await?;
     }
-    
-    sleep(Duration::from_secs(10)).await;
+
     println!("✅ Sample NGINX app deployed");
     Ok(())
 }
 
-fn deploy_basic_nginx() -> Result<()> {
+async fn deploy_basic_nginx() -> Result<()> {
     let nginx_manifest = r#"
 apiVersion: apps/v1
 kind: Deployment
@@ -604,131 +1122,36 @@ spec:
     targetPort: 80
 "#;
     
-    utils::apply_manifest(nginx_manifest)?;
+    crate::k8s::apply_manifest(nginx_manifest).await?;
     Ok(())
 }
 
-fn setup_namespaces() -> Result<()> {
+async fn setup_namespaces() -> Result<()> {
     println!("\n📂 Creating application namespaces...");
     
-    let manifest_file = get_manifest_file("namespaces");
-    if Path::new(&manifest_file).exists() {
-        utils::run("kubectl", &["apply", "-f", &manifest_file])?;
-    } else {
-        // Fallback to inline manifest
-        let namespaces = r#"
-apiVersion: v1
-kind: Namespace
-metadata:
-  name: production
-  labels:
-    environment: production
----
-apiVersion: v1
-kind: Namespace
-metadata:
-  name: staging
-  labels:
-    environment: staging
----
-apiVersion: v1
-kind: Namespace
-metadata:
-  name: development
-  labels:
-    environment: development
-"#;
-        utils::apply_manifest(namespaces)?;
-    }
-    
+    let namespaces = resolve_manifest("namespaces", NAMESPACES_MANIFEST_FALLBACK)?;
+    crate::k8s::apply_manifest(&namespaces).await?;
+
     println!("✅ Namespaces created");
     Ok(())
 }
 
-fn setup_network_policies() -> Result<()> {
+async fn setup_network_policies() -> Result<()> {
     println!("\n🔒 Setting up network policies...");
-    
-    let manifest_file = get_manifest_file("network-policies");
-    if Path::new(&manifest_file).exists() {
-        utils::run("kubectl", &["apply", "-f", &manifest_file])?;
-    } else {
-        // Fallback to inline manifest
-        let policies = r#"
-apiVersion: networking.k8s.io/v1
-kind: NetworkPolicy
-metadata:
-  name: default-deny-ingress
-  namespace: production
-spec:
-  podSelector: {}
-  policyTypes:
-  - Ingress
----
-apiVersion: networking.k8s.io/v1
-kind: NetworkPolicy
-metadata:
-  name: allow-same-namespace
-  namespace: production
-spec:
-  podSelector: {}
-  policyTypes:
-  - Ingress
-  ingress:
-  - from:
-    - podSelector: {}
-"#;
-        utils::apply_manifest(policies)?;
-    }
-    
+
+    let policies = resolve_manifest("network-policies", NETWORK_POLICIES_MANIFEST_FALLBACK)?;
+    crate::k8s::apply_manifest(&policies).await?;
+
     println!("✅ Network policies applied");
     Ok(())
 }
 
-fn setup_resource_quotas() -> Result<()> {
+async fn setup_resource_quotas() -> Result<()> {
     println!("\n💾 Setting up resource quotas...");
-    
-    let manifest_file = get_manifest_file("resource-quotas");
-    if Path::new(&manifest_file).exists() {
-        utils::run("kubectl", &["apply", "-f", &manifest_file])?;
-    } else {
-        // Fallback to inline manifest
-        let quotas = r#"
-apiVersion: v1
-kind: ResourceQuota
-metadata:
-  name: compute-quota
-  namespace: production
-spec:
-  hard:
-    requests.cpu: "10"
-    requests.memory: 20Gi
-    limits.cpu: "20"
-    limits.memory: 40Gi
----
-apiVersion: v1
-kind: LimitRange
-metadata:
-  name: resource-limits
-  namespace: production
-spec:
-  limits:
-  - max:
-      cpu: "2"
-      memory: 4Gi
-    min:
-      cpu: 100m
-      memory: 128Mi
-    default:
-      cpu: 500m
-      memory: 512Mi
-    defaultRequest:
-      cpu: 250m
-      memory: 256Mi
-    type: Container
-"#;
-        utils::apply_manifest(quotas)?;
-    }
-    
+
+    let quotas = resolve_manifest("resource-quotas", RESOURCE_QUOTAS_MANIFEST_FALLBACK)?;
+    crate::k8s::apply_manifest(&quotas).await?;
+
     println!("✅ Resource quotas set");
     Ok(())
 }
@@ -750,19 +1173,35 @@ fn print_basic_access_info(cluster_name: &str) {
     println!("\n{}", separator);
 }
 
-fn print_access_info(cluster_name: &str) {
+fn print_access_info(cluster_name: &str, sso: bool) {
     let separator = "=".repeat(60);
     println!("\n{}", separator);
     println!("🎯 Access Information for '{}':", cluster_name);
     println!("{}", separator);
     println!("\n📊 Monitoring:");
     println!("  Prometheus: http://localhost:9090");
-    println!("  Grafana:    http://localhost:3000 (admin/admin)");
+    if sso {
+        println!("  Grafana:    http://localhost:3000 (sign in via Dex)");
+    } else {
+        println!("  Grafana:    http://localhost:3000 (admin/admin)");
+    }
     println!("\n📝 Logging:");
-    println!("  Kibana:     http://localhost:5601");
+    if sso {
+        println!("  Kibana:     http://localhost:5601 (sign in via Dex)");
+    } else {
+        println!("  Kibana:     http://localhost:5601");
+    }
     println!("\n🔄 GitOps:");
-    println!("  ArgoCD:     http://localhost:8080 (admin)");
-    println!("  Password:   kubectl -n argocd get secret argocd-initial-admin-secret -o jsonpath=\"{{.data.password}}\" | base64 -d");
+    if sso {
+        println!("  ArgoCD:     http://localhost:8080 (sign in via Dex)");
+    } else {
+        println!("  ArgoCD:     http://localhost:8080 (admin)");
+        println!("  Password:   kubectl -n argocd get secret argocd-initial-admin-secret -o jsonpath=\"{{.data.password}}\" | base64 -d");
+    }
+    if sso {
+        println!("\n🔑 Single Sign-On:");
+        println!("  Dex:        https://dex.local (unified login for Grafana, ArgoCD, and Kibana)");
+    }
     println!("\n🌐 Sample App:");
     println!("  Add to /etc/hosts: 127.0.0.1 nginx.local");
     println!("  Then visit: http://nginx.local");
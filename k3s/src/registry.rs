@@ -0,0 +1,82 @@
+// registry.rs
+//! Local image-registry subsystem (the `Registry` subcommand).
+//!
+//! A `k3d registry` is a single container shared across clusters, so
+//! images built on the host can be `docker push`ed once to
+//! `localhost:<port>/img` and pulled by any cluster it's wired into,
+//! instead of re-pushing per cluster.
+
+use crate::utils;
+use anyhow::{Context, Result};
+use std::fs;
+
+/// The docker network name k3d gives a registry named `name`, used both
+/// in a cluster's `registries.yaml` mirror config and with
+/// `k3d cluster edit --registry-use`.
+pub fn container_name(name: &str) -> String {
+    format!("k3d-{}", name)
+}
+
+/// Creates a new registry via `k3d registry create`.
+pub fn create(name: &str, port: u16) -> Result<()> {
+    println!("📦 Creating registry '{}' on port {}...", name, port);
+    utils::run("k3d", &["registry", "create", name, "--port", &port.to_string()])?;
+    println!("✅ Registry '{}' created: localhost:{}", name, port);
+    Ok(())
+}
+
+/// Creates the registry if it doesn't already exist, or reuses it, so
+/// `--registry` on `dev`/`prod` is idempotent across repeated cluster
+/// creations instead of failing the second time around.
+pub fn ensure(name: &str, port: u16) -> Result<()> {
+    match create(name, port) {
+        Ok(()) => Ok(()),
+        Err(err) if err.to_string().contains("already exists") => {
+            println!("ℹ️  Registry '{}' already exists, reusing it", name);
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes a registry via `k3d registry delete`.
+pub fn delete(name: &str) -> Result<()> {
+    println!("🗑️  Deleting registry: {}", name);
+    utils::run("k3d", &["registry", "delete", name])?;
+    println!("✅ Registry '{}' deleted", name);
+    Ok(())
+}
+
+/// Lists registries via `k3d registry list`.
+pub fn list() -> Result<()> {
+    println!("📋 K3D Registries:\n");
+    utils::run("k3d", &["registry", "list"])?;
+    Ok(())
+}
+
+/// Wires an existing registry into an already-running cluster's containerd
+/// mirror config via `k3d cluster edit --registry-use`, the same mechanism
+/// k3d uses when a registry is attached at cluster-create time.
+pub fn connect(registry_name: &str, cluster_name: &str) -> Result<()> {
+    println!("🔗 Connecting registry '{}' to cluster '{}'...", registry_name, cluster_name);
+    utils::run(
+        "k3d",
+        &["cluster", "edit", cluster_name, "--registry-use", &container_name(registry_name)],
+    )?;
+    println!("✅ Registry '{}' connected to cluster '{}'", registry_name, cluster_name);
+    Ok(())
+}
+
+/// Writes a standalone k3d `registries.yaml` mirroring `localhost:<port>`
+/// to the registry's container, for passing to `k3d cluster create
+/// --registry-config <path>` so images pushed to `localhost:<port>/img`
+/// on the host are immediately pullable in-cluster.
+pub fn write_mirror_config(path: &str, name: &str, port: u16) -> Result<()> {
+    let yaml = format!(
+        "mirrors:\n  \"localhost:{port}\":\n    endpoint:\n      - \"http://{container}:{port}\"\n",
+        port = port,
+        container = container_name(name),
+    );
+    fs::write(path, yaml).context("failed to write registries.yaml")?;
+    Ok(())
+}
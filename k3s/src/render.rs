@@ -0,0 +1,232 @@
+// render.rs
+//! `render` mode: resolves a `ProdClusterConfig` into the full set of
+//! manifests it would install and writes them to an output directory as
+//! ordered, numbered files plus a generated `apply.sh` — no `helm install`
+//! or `kubectl apply` is ever run, and no cluster is created. The sample
+//! app that `prod` deploys is not rendered.
+
+use crate::prod_cluster::{
+    get_values_file, resolve_manifest, ProdClusterConfig, ARGOCD_OIDC_SET, ARGOCD_OIDC_SET_STRING,
+    CERT_ISSUER_MANIFEST_FALLBACK, DEX_TLS_MANIFEST_FALLBACK, GRAFANA_OIDC_SETS, KIBANA_OIDC_SET,
+    NAMESPACES_MANIFEST_FALLBACK, NETWORK_POLICIES_MANIFEST_FALLBACK,
+    RESOURCE_QUOTAS_MANIFEST_FALLBACK,
+};
+use anyhow::{Context, Result};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+struct RenderedFile {
+    /// File name under the output directory, e.g. "01-cert-manager.yaml".
+    name: String,
+    content: String,
+}
+
+fn helm_template(
+    label: &str,
+    chart: &str,
+    namespace: &str,
+    default_sets: &[&str],
+    default_set_strings: &[&str],
+) -> Result<String> {
+    let values_file = get_values_file(label);
+    let resolved_values = if Path::new(&values_file).exists() {
+        Some(crate::sops::resolve_file(&values_file)?)
+    } else {
+        None
+    };
+
+    let mut args = vec![
+        "template".to_string(),
+        label.to_string(),
+        chart.to_string(),
+        "--namespace".to_string(),
+        namespace.to_string(),
+    ];
+
+    if let Some(resolved) = &resolved_values {
+        args.push("--values".to_string());
+        args.push(resolved.as_str().to_string());
+    } else {
+        for set in default_sets {
+            args.push("--set".to_string());
+            args.push(set.to_string());
+        }
+        for set in default_set_strings {
+            args.push("--set-string".to_string());
+            args.push(set.to_string());
+        }
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = Command::new("helm")
+        .args(&arg_refs)
+        .output()
+        .with_context(|| format!("failed to run helm template for {}", label))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "helm template {} failed:\n{}",
+            label,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Resolves `config` into ordered manifest files and an `apply.sh`, and
+/// writes them under `output_dir`. Nothing is installed or applied.
+pub fn render(config: &ProdClusterConfig, output_dir: &Path) -> Result<()> {
+    println!(
+        "📄 Rendering manifests for '{}' into {}\n",
+        config.name,
+        output_dir.display()
+    );
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("failed to create output directory: {}", output_dir.display()))?;
+
+    let mut files = vec![
+        RenderedFile {
+            name: "00-namespaces.yaml".to_string(),
+            content: resolve_manifest("namespaces", NAMESPACES_MANIFEST_FALLBACK)?,
+        },
+        RenderedFile {
+            name: "01-cert-manager.yaml".to_string(),
+            content: helm_template(
+                "cert-manager",
+                "jetstack/cert-manager",
+                "cert-manager",
+                &["installCRDs=true"],
+                &[],
+            )?,
+        },
+        RenderedFile {
+            name: "02-cert-issuer.yaml".to_string(),
+            content: resolve_manifest("cert-issuer", CERT_ISSUER_MANIFEST_FALLBACK)?,
+        },
+        RenderedFile {
+            name: "03-ingress-nginx.yaml".to_string(),
+            content: helm_template(
+                "ingress-nginx",
+                "ingress-nginx/ingress-nginx",
+                "ingress-nginx",
+                &["controller.hostPort.enabled=true", "controller.service.type=NodePort"],
+                &[],
+            )?,
+        },
+        RenderedFile {
+            name: "04-network-policies.yaml".to_string(),
+            content: resolve_manifest("network-policies", NETWORK_POLICIES_MANIFEST_FALLBACK)?,
+        },
+        RenderedFile {
+            name: "05-resource-quotas.yaml".to_string(),
+            content: resolve_manifest("resource-quotas", RESOURCE_QUOTAS_MANIFEST_FALLBACK)?,
+        },
+    ];
+
+    let grafana_sso_sets: Vec<String> = GRAFANA_OIDC_SETS.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let grafana_sso_sets: Vec<&str> = grafana_sso_sets.iter().map(String::as_str).collect();
+    let kibana_sso_set = format!("{}={}", KIBANA_OIDC_SET.0, KIBANA_OIDC_SET.1);
+    let argocd_sso_set = format!("{}={}", ARGOCD_OIDC_SET.0, ARGOCD_OIDC_SET.1);
+    let argocd_sso_set_string = format!("{}={}", ARGOCD_OIDC_SET_STRING.0, ARGOCD_OIDC_SET_STRING.1);
+
+    if config.install_monitoring {
+        files.push(RenderedFile {
+            name: "06-kube-prometheus-stack.yaml".to_string(),
+            content: helm_template(
+                "kube-prometheus-stack",
+                "prometheus-community/kube-prometheus-stack",
+                "monitoring",
+                if config.install_sso { grafana_sso_sets.as_slice() } else { &[] },
+                &[],
+            )?,
+        });
+    }
+
+    if config.install_logging {
+        files.push(RenderedFile {
+            name: "07-elasticsearch.yaml".to_string(),
+            content: helm_template(
+                "elasticsearch",
+                "elastic/elasticsearch",
+                "logging",
+                &["replicas=1", "minimumMasterNodes=1"],
+                &[],
+            )?,
+        });
+        files.push(RenderedFile {
+            name: "08-fluentd.yaml".to_string(),
+            content: helm_template("fluentd", "fluent/fluentd", "logging", &[], &[])?,
+        });
+        files.push(RenderedFile {
+            name: "09-kibana.yaml".to_string(),
+            content: helm_template(
+                "kibana",
+                "elastic/kibana",
+                "logging",
+                if config.install_sso { &[kibana_sso_set.as_str()] } else { &[] },
+                &[],
+            )?,
+        });
+    }
+
+    if config.install_argocd {
+        files.push(RenderedFile {
+            name: "10-argocd.yaml".to_string(),
+            content: helm_template(
+                "argocd",
+                "argo/argo-cd",
+                "argocd",
+                if config.install_sso { &[argocd_sso_set.as_str()] } else { &[] },
+                if config.install_sso { &[argocd_sso_set_string.as_str()] } else { &[] },
+            )?,
+        });
+    }
+
+    if config.install_sso {
+        files.push(RenderedFile {
+            name: "11-dex-tls.yaml".to_string(),
+            content: resolve_manifest("dex-tls", DEX_TLS_MANIFEST_FALLBACK)?,
+        });
+        files.push(RenderedFile {
+            name: "12-dex.yaml".to_string(),
+            content: helm_template("dex", "dex/dex", "dex", &[], &[])?,
+        });
+    }
+
+    for file in &files {
+        let path = output_dir.join(&file.name);
+        fs::write(&path, &file.content)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("   ✅ {}", file.name);
+    }
+
+    write_apply_script(output_dir, &files)?;
+
+    println!("\n🎉 Rendered {} manifests to {}", files.len(), output_dir.display());
+    println!("   Review them, then: ./{}/apply.sh", output_dir.display());
+
+    Ok(())
+}
+
+fn write_apply_script(output_dir: &Path, files: &[RenderedFile]) -> Result<()> {
+    let mut script = String::from("#!/usr/bin/env bash\nset -euo pipefail\n\ncd \"$(dirname \"$0\")\"\n\n");
+    for file in files {
+        script.push_str(&format!("echo \"Applying {}...\"\n", file.name));
+        script.push_str(&format!("kubectl apply -f {}\n", file.name));
+    }
+
+    let script_path: PathBuf = output_dir.join("apply.sh");
+    fs::write(&script_path, script)
+        .with_context(|| format!("failed to write {}", script_path.display()))?;
+
+    let mut permissions = fs::metadata(&script_path)?.permissions();
+    permissions.set_mode(0o755);
+    fs::set_permissions(&script_path, permissions)?;
+
+    println!("   ✅ apply.sh");
+    Ok(())
+}
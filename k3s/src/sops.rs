@@ -0,0 +1,103 @@
+// sops.rs
+//! Transparent SOPS+age decryption for Helm values files and manifests.
+//!
+//! Any values file under `HELM_VALUES_DIR` or manifest under `manifests/`
+//! named `*.sops.yaml` is treated as SOPS-encrypted. It's decrypted with
+//! `sops --decrypt` (which reads `SOPS_AGE_KEY_FILE` from the environment
+//! itself) before being handed to helm or kubectl.
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn is_encrypted(path: &str) -> bool {
+    path.ends_with(".sops.yaml")
+}
+
+fn decrypt(path: &str) -> Result<Vec<u8>> {
+    let output = Command::new("sops")
+        .args(&["--decrypt", path])
+        .output()
+        .with_context(|| format!("failed to run sops --decrypt on {}", path))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "sops --decrypt failed for {}:\n{}",
+            path,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(output.stdout)
+}
+
+/// Reads `path` as plaintext, transparently decrypting it first if its name
+/// ends in `.sops.yaml`.
+pub fn read_to_string(path: &str) -> Result<String> {
+    if is_encrypted(path) {
+        let plaintext = decrypt(path)?;
+        Ok(String::from_utf8_lossy(&plaintext).to_string())
+    } else {
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path))
+    }
+}
+
+/// A values/manifest path resolved to plaintext on disk. Non-encrypted
+/// paths pass through unchanged; `.sops.yaml` paths are decrypted to a
+/// `0600` temp file that's deleted when this value is dropped.
+pub struct ResolvedFile {
+    path: PathBuf,
+    temp: bool,
+}
+
+impl ResolvedFile {
+    pub fn as_str(&self) -> &str {
+        self.path.to_str().unwrap_or_default()
+    }
+}
+
+impl Drop for ResolvedFile {
+    fn drop(&mut self) {
+        if self.temp {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Resolves `path` so it can be passed as a real file (e.g. to `helm
+/// --values`). Decrypts `.sops.yaml` paths to a `0600` temp file; anything
+/// else is returned unchanged.
+pub fn resolve_file(path: &str) -> Result<ResolvedFile> {
+    if !is_encrypted(path) {
+        return Ok(ResolvedFile {
+            path: PathBuf::from(path),
+            temp: false,
+        });
+    }
+
+    let plaintext = decrypt(path)?;
+
+    let file_name = Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("values.yaml")
+        .trim_end_matches(".sops.yaml");
+    let temp_path = std::env::temp_dir().join(format!("{}-{}.yaml", file_name, std::process::id()));
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&temp_path)
+        .with_context(|| format!("failed to create decrypted file: {}", temp_path.display()))?;
+    file.write_all(&plaintext)
+        .with_context(|| format!("failed to write decrypted file: {}", temp_path.display()))?;
+
+    Ok(ResolvedFile {
+        path: temp_path,
+        temp: true,
+    })
+}
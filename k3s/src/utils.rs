@@ -2,6 +2,52 @@ use anyhow::{Context, Result};
 use std::process::Command;
 use std::io::Write;
 
+/// Required binaries checked by `check_tools`, each with the args that
+/// print its version without side effects. `kubectl version` alone tries
+/// to contact the API server and fails with no cluster reachable yet, so
+/// it needs `--client` to stay a local, side-effect-free check like the
+/// other two.
+const REQUIRED_TOOLS: &[(&str, &[&str])] = &[
+    ("docker", &["--version"]),
+    ("k3d", &["version"]),
+    ("kubectl", &["version", "--client"]),
+];
+
+/// Preflight check run before any cluster operation dispatches: confirms
+/// `docker`, `k3d`, and `kubectl` are on PATH (capturing their version)
+/// and that the Docker daemon's API is actually responding, so a missing
+/// tool or a stopped daemon surfaces as one clear diagnostic instead of a
+/// confusing `failed to run` error from deep inside some later command.
+pub fn check_tools() -> Result<()> {
+    for (tool, version_args) in REQUIRED_TOOLS {
+        let output = Command::new(tool).args(*version_args).output().with_context(|| {
+            format!("'{}' not found on PATH - is it installed?", tool)
+        })?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "'{} {}' failed:\n{}",
+                tool,
+                version_args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let version = String::from_utf8_lossy(&output.stdout);
+        eprintln!("✅ {}: {}", tool, version.lines().next().unwrap_or("").trim());
+    }
+
+    let docker_info = Command::new("docker")
+        .arg("info")
+        .output()
+        .context("failed to run 'docker info'")?;
+    if !docker_info.status.success() {
+        anyhow::bail!("Docker API not reachable — is the daemon running?");
+    }
+
+    Ok(())
+}
+
 pub fn run(cmd: &str, args: &[&str]) -> Result<()> {
     let output = Command::new(cmd)
         .args(args)
@@ -25,29 +71,35 @@ pub fn run(cmd: &str, args: &[&str]) -> Result<()> {
     Ok(())
 }
 
-pub fn apply_manifest(manifest: &str) -> Result<()> {
+/// Like `crate::k8s::apply_manifest`, but validates without persisting
+/// anything via `kubectl apply --dry-run`. Pass `server_side: true` to validate against
+/// a reachable API server (`--dry-run=server`), or `false` to fall back to
+/// client-side schema validation when no cluster is available.
+pub fn apply_manifest_dry_run(manifest: &str, server_side: bool) -> Result<()> {
+    let dry_run_flag = if server_side { "--dry-run=server" } else { "--dry-run=client" };
+
     let mut child = Command::new("kubectl")
-        .args(&["apply", "-f", "-"])
+        .args(&["apply", dry_run_flag, "-f", "-"])
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
         .context("Failed to spawn kubectl")?;
-    
+
     if let Some(mut stdin) = child.stdin.take() {
         stdin.write_all(manifest.as_bytes())
             .context("Failed to write manifest to kubectl stdin")?;
     }
-    
+
     let output = child.wait_with_output()
         .context("Failed to wait for kubectl")?;
-    
+
     if !output.status.success() {
         anyhow::bail!(
-            "kubectl apply failed:\n{}",
+            "kubectl apply --dry-run failed:\n{}",
             String::from_utf8_lossy(&output.stderr)
         );
     }
-    
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,199 @@
+// validate.rs
+//! `--dry-run` preflight: templates every Helm chart a `ProdClusterConfig`
+//! would install and validates the inline manifests it applies, without
+//! provisioning or mutating a cluster. The sample-app chart and the
+//! cert-issuer manifest are not checked.
+
+use crate::prod_cluster::{
+    get_values_file, resolve_manifest, ProdClusterConfig, ARGOCD_OIDC_SET, ARGOCD_OIDC_SET_STRING,
+    DEX_TLS_MANIFEST_FALLBACK, GRAFANA_OIDC_SETS, KIBANA_OIDC_SET, NAMESPACES_MANIFEST_FALLBACK,
+    NETWORK_POLICIES_MANIFEST_FALLBACK, RESOURCE_QUOTAS_MANIFEST_FALLBACK,
+};
+use crate::utils;
+use anyhow::{bail, Result};
+use std::path::Path;
+use std::process::Command;
+
+struct CheckResult {
+    label: String,
+    error: Option<String>,
+}
+
+fn helm_template_check(
+    label: &str,
+    chart: &str,
+    namespace: &str,
+    default_sets: &[&str],
+    default_set_strings: &[&str],
+) -> CheckResult {
+    let values_file = get_values_file(label);
+    let resolved_values = if Path::new(&values_file).exists() {
+        match crate::sops::resolve_file(&values_file) {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                return CheckResult {
+                    label: label.to_string(),
+                    error: Some(format!("failed to decrypt values file: {}", e)),
+                }
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut args = vec!["template", label, chart, "--namespace", namespace];
+    if let Some(resolved) = &resolved_values {
+        args.push("--values");
+        args.push(resolved.as_str());
+    } else {
+        for set in default_sets {
+            args.push("--set");
+            args.push(set);
+        }
+        for set in default_set_strings {
+            args.push("--set-string");
+            args.push(set);
+        }
+    }
+
+    let error = match Command::new("helm").args(&args).output() {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Some(format!("failed to run helm template: {}", e)),
+    };
+
+    CheckResult {
+        label: label.to_string(),
+        error,
+    }
+}
+
+fn manifest_check(label: &str, name: &str, fallback: &str, server_side: bool) -> CheckResult {
+    let manifest = match resolve_manifest(name, fallback) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            return CheckResult {
+                label: label.to_string(),
+                error: Some(format!("failed to resolve manifest: {}", e)),
+            }
+        }
+    };
+    let error = utils::apply_manifest_dry_run(&manifest, server_side)
+        .err()
+        .map(|e| e.to_string());
+
+    CheckResult {
+        label: label.to_string(),
+        error,
+    }
+}
+
+fn cluster_reachable() -> bool {
+    Command::new("kubectl")
+        .args(&["cluster-info"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Runs every template/manifest check for `config` and reports a pass/fail
+/// summary. Returns an error naming every failing check if any fail; does
+/// not create a cluster or apply anything.
+pub fn validate(config: &ProdClusterConfig) -> Result<()> {
+    println!("🔎 Dry-run validation for '{}' (nothing will be created)\n", config.name);
+
+    let server_side = cluster_reachable();
+    if server_side {
+        println!("   Manifests will be checked server-side against the current kube context\n");
+    } else {
+        println!("   No reachable cluster found, falling back to client-side manifest checks\n");
+    }
+
+    let grafana_sso_sets: Vec<String> = GRAFANA_OIDC_SETS.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let grafana_sso_sets: Vec<&str> = grafana_sso_sets.iter().map(String::as_str).collect();
+    let kibana_sso_set = format!("{}={}", KIBANA_OIDC_SET.0, KIBANA_OIDC_SET.1);
+    let argocd_sso_set = format!("{}={}", ARGOCD_OIDC_SET.0, ARGOCD_OIDC_SET.1);
+    let argocd_sso_set_string = format!("{}={}", ARGOCD_OIDC_SET_STRING.0, ARGOCD_OIDC_SET_STRING.1);
+
+    let mut results = vec![
+        helm_template_check("cert-manager", "jetstack/cert-manager", "cert-manager", &[], &[]),
+        helm_template_check("ingress-nginx", "ingress-nginx/ingress-nginx", "ingress-nginx", &[], &[]),
+    ];
+
+    if config.install_monitoring {
+        results.push(helm_template_check(
+            "kube-prometheus-stack",
+            "prometheus-community/kube-prometheus-stack",
+            "monitoring",
+            if config.install_sso { grafana_sso_sets.as_slice() } else { &[] },
+            &[],
+        ));
+    }
+
+    if config.install_logging {
+        results.push(helm_template_check("elasticsearch", "elastic/elasticsearch", "logging", &[], &[]));
+        results.push(helm_template_check("fluentd", "fluent/fluentd", "logging", &[], &[]));
+        results.push(helm_template_check(
+            "kibana",
+            "elastic/kibana",
+            "logging",
+            if config.install_sso { &[kibana_sso_set.as_str()] } else { &[] },
+            &[],
+        ));
+    }
+
+    if config.install_argocd {
+        results.push(helm_template_check(
+            "argocd",
+            "argo/argo-cd",
+            "argocd",
+            if config.install_sso { &[argocd_sso_set.as_str()] } else { &[] },
+            if config.install_sso { &[argocd_sso_set_string.as_str()] } else { &[] },
+        ));
+    }
+
+    if config.install_sso {
+        results.push(helm_template_check("dex", "dex/dex", "dex", &[], &[]));
+        results.push(manifest_check("dex-tls", "dex-tls", DEX_TLS_MANIFEST_FALLBACK, server_side));
+    }
+
+    results.push(manifest_check(
+        "namespaces",
+        "namespaces",
+        NAMESPACES_MANIFEST_FALLBACK,
+        server_side,
+    ));
+    results.push(manifest_check(
+        "network-policies",
+        "network-policies",
+        NETWORK_POLICIES_MANIFEST_FALLBACK,
+        server_side,
+    ));
+    results.push(manifest_check(
+        "resource-quotas",
+        "resource-quotas",
+        RESOURCE_QUOTAS_MANIFEST_FALLBACK,
+        server_side,
+    ));
+
+    let mut failures = Vec::new();
+    for result in &results {
+        match &result.error {
+            None => println!("   ✅ {}", result.label),
+            Some(detail) => {
+                eprintln!("   ❌ {}: {}", result.label, detail);
+                failures.push(result.label.as_str());
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "dry-run validation failed for: {} (see stderr above for details)",
+            failures.join(", ")
+        );
+    }
+
+    println!("\n✅ All checks passed — safe to run without --dry-run");
+    Ok(())
+}